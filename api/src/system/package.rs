@@ -3,17 +3,192 @@ use std::collections::{HashMap, HashSet};
 use packageurl::PackageUrl;
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, EntityTrait, FromQueryResult,
-    ModelTrait, QueryFilter, QuerySelect, Set, Statement,
+    ModelTrait, PaginatorTrait, QueryFilter, QuerySelect, Set, Statement,
 };
+use sea_query::OnConflict;
 use sea_query::Value;
 
 use huevos_entity::package::{PackageNamespace, PackageType};
-use huevos_entity::package_dependency::ToDependency;
+use huevos_entity::package_dependency::{ToDependency, ToDependent};
 use huevos_entity::{package, package_dependency, package_qualifier};
 
 use crate::system::System;
 use crate::{PackageTree, Purl};
 
+/// The freshness of a single ingested version relative to the newest version
+/// known for its `(package_type, package_namespace, package_name)` group.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PackageStatus {
+    /// This version *is* the latest known version for the package.
+    UpToDate,
+    /// A newer version is known to exist.
+    Outdated { latest: String },
+    /// The version string could not be compared against its siblings.
+    Unparseable,
+}
+
+/// A version comparator for a specific `package_type`, used to decide which
+/// of a set of ingested versions is "latest".
+type VersionComparator = fn(&str, &str) -> Option<std::cmp::Ordering>;
+
+/// Compare two versions using semver (`major.minor.patch[-pre]`) rules.
+fn compare_semver(a: &str, b: &str) -> Option<std::cmp::Ordering> {
+    fn parse(v: &str) -> Option<(u64, u64, u64, Option<String>)> {
+        let (core, pre) = match v.split_once('-') {
+            Some((core, pre)) => (core, Some(pre.to_string())),
+            None => (v, None),
+        };
+        let mut parts = core.split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next().unwrap_or("0").parse().ok()?;
+        let patch = parts.next().unwrap_or("0").parse().ok()?;
+        if parts.next().is_some() {
+            return None;
+        }
+        Some((major, minor, patch, pre))
+    }
+
+    let a = parse(a)?;
+    let b = parse(b)?;
+
+    Some((a.0, a.1, a.2).cmp(&(b.0, b.1, b.2)).then_with(|| {
+        // A pre-release is older than its corresponding release.
+        match (&a.3, &b.3) {
+            (None, None) => std::cmp::Ordering::Equal,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (Some(a), Some(b)) => a.cmp(b),
+        }
+    }))
+}
+
+/// Rank a Maven-style qualifier token so that `SNAPSHOT < alpha < beta < rc < <release>`.
+fn maven_qualifier_rank(token: &str) -> u8 {
+    match token.to_ascii_lowercase().as_str() {
+        "snapshot" => 0,
+        "alpha" | "a" => 1,
+        "beta" | "b" => 2,
+        "rc" | "cr" => 3,
+        _ => 4,
+    }
+}
+
+/// Compare two versions the way Maven compares artifact versions: split on
+/// `.` and `-`, compare numeric tokens numerically and alphabetic tokens by
+/// qualifier rank, falling back to a lexical compare of the token itself.
+fn compare_maven(a: &str, b: &str) -> Option<std::cmp::Ordering> {
+    let split = |v: &str| -> Vec<String> {
+        v.split(['.', '-']).map(|s| s.to_string()).collect()
+    };
+
+    let a_tokens = split(a);
+    let b_tokens = split(b);
+
+    for pair in a_tokens.iter().zip(b_tokens.iter()) {
+        let (a_tok, b_tok) = pair;
+        let ordering = match (a_tok.parse::<u64>(), b_tok.parse::<u64>()) {
+            (Ok(a_num), Ok(b_num)) => a_num.cmp(&b_num),
+            (Ok(_), Err(_)) => std::cmp::Ordering::Greater,
+            (Err(_), Ok(_)) => std::cmp::Ordering::Less,
+            (Err(_), Err(_)) => maven_qualifier_rank(a_tok)
+                .cmp(&maven_qualifier_rank(b_tok))
+                .then_with(|| a_tok.cmp(b_tok)),
+        };
+
+        if ordering != std::cmp::Ordering::Equal {
+            return Some(ordering);
+        }
+    }
+
+    Some(a_tokens.len().cmp(&b_tokens.len()))
+}
+
+/// Last-resort comparator: plain lexical ordering.
+fn compare_lexical(a: &str, b: &str) -> Option<std::cmp::Ordering> {
+    Some(a.cmp(b))
+}
+
+/// Pick the comparator chain appropriate for a `package_type`. Every
+/// ecosystem currently falls back through semver, then Maven-style tokens,
+/// then lexical comparison, trying each in turn until one of them can parse
+/// both sides.
+fn comparators_for(_package_type: &str) -> &'static [VersionComparator] {
+    &[compare_semver, compare_maven, compare_lexical]
+}
+
+/// Compare two versions for a given `package_type`, using whichever
+/// comparator in the chain is the first able to parse both versions.
+fn compare_versions(package_type: &str, a: &str, b: &str) -> Option<std::cmp::Ordering> {
+    comparators_for(package_type)
+        .iter()
+        .find_map(|cmp| cmp(a, b))
+}
+
+impl System {
+    /// Report, for every distinct package coordinate (ignoring `version` and
+    /// qualifiers), which ingested versions are behind the newest one we
+    /// know about.
+    pub async fn outdated_packages(
+        &self,
+    ) -> Result<HashMap<(String, Option<String>, String), (String, Vec<(Purl, PackageStatus)>)>, anyhow::Error>
+    {
+        let found = package::Entity::find()
+            .find_with_related(package_qualifier::Entity)
+            .all(&*self.db)
+            .await?;
+
+        let mut groups: HashMap<(String, Option<String>, String), Vec<(package::Model, Vec<package_qualifier::Model>)>> =
+            HashMap::new();
+
+        for (base, qualifiers) in found {
+            let key = (
+                base.package_type.clone(),
+                base.package_namespace.clone(),
+                base.package_name.clone(),
+            );
+            groups.entry(key).or_default().push((base, qualifiers));
+        }
+
+        let mut result = HashMap::new();
+
+        for (key, members) in groups {
+            let package_type = &key.0;
+
+            let latest = members
+                .iter()
+                .map(|(base, _)| base.version.clone())
+                .reduce(|a, b| {
+                    match compare_versions(package_type, &a, &b) {
+                        Some(std::cmp::Ordering::Less) => b,
+                        _ => a,
+                    }
+                })
+                .unwrap_or_default();
+
+            let mut statuses = Vec::new();
+
+            for (base, qualifiers) in &members {
+                let purl = self.package_to_purl(base.clone(), qualifiers.clone())?;
+
+                let status = match compare_versions(package_type, &base.version, &latest) {
+                    None => PackageStatus::Unparseable,
+                    Some(std::cmp::Ordering::Equal) => PackageStatus::UpToDate,
+                    Some(std::cmp::Ordering::Less) => PackageStatus::Outdated {
+                        latest: latest.clone(),
+                    },
+                    Some(std::cmp::Ordering::Greater) => PackageStatus::UpToDate,
+                };
+
+                statuses.push((purl, status));
+            }
+
+            result.insert(key, (latest, statuses));
+        }
+
+        Ok(result)
+    }
+}
+
 impl System {
     pub async fn ingest_package<'p, P: Into<Purl>>(
         &self,
@@ -60,6 +235,221 @@ impl System {
         Ok(self.packages_to_purls(found)?)
     }
 
+    /// Paginated variant of [`Self::packages`], for stores too large to
+    /// load into memory in one shot.
+    pub async fn packages_paginated(
+        &self,
+        per_page: u64,
+        page: u64,
+    ) -> Result<(u64, Vec<Purl>), anyhow::Error> {
+        let paginator = package::Entity::find().paginate(&*self.db, per_page);
+
+        let total_pages = paginator.num_pages().await?;
+        let page_packages = paginator.fetch_page(page).await?;
+
+        let package_ids: Vec<_> = page_packages.iter().map(|pkg| pkg.id).collect();
+
+        let qualifiers = package_qualifier::Entity::find()
+            .filter(package_qualifier::Column::PackageId.is_in(package_ids))
+            .all(&*self.db)
+            .await?;
+
+        let found = page_packages
+            .into_iter()
+            .map(|pkg| {
+                let pkg_qualifiers = qualifiers
+                    .iter()
+                    .filter(|q| q.package_id == pkg.id)
+                    .cloned()
+                    .collect();
+                (pkg, pkg_qualifiers)
+            })
+            .collect();
+
+        Ok((total_pages, self.packages_to_purls(found)?))
+    }
+
+    /// Bulk-ingest a set of packages, replacing the per-row
+    /// SELECT-then-INSERT of [`Self::insert_or_fetch_package`] with a single
+    /// resolution query (one OR-ed condition per distinct purl) plus a single batch insert for the
+    /// packages (and their qualifiers) that are genuinely new. Returns the
+    /// resolved `package::Model`s in input order.
+    pub async fn ingest_packages<P: Into<Purl>>(
+        &self,
+        purls: impl IntoIterator<Item = P>,
+    ) -> Result<Vec<package::Model>, anyhow::Error> {
+        let purls: Vec<Purl> = purls.into_iter().map(Into::into).collect();
+
+        let mut seen = HashSet::new();
+        let unique: Vec<_> = purls
+            .iter()
+            .filter(|purl| seen.insert(Self::purl_key(purl)))
+            .cloned()
+            .collect();
+
+        if unique.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let existing = package::Entity::find()
+            .filter(Self::purls_condition(&unique))
+            .find_with_related(package_qualifier::Entity)
+            .all(&*self.db)
+            .await?;
+
+        let mut resolved: HashMap<_, package::Model> = existing
+            .into_iter()
+            .map(|(pkg, qualifiers)| (Self::package_key(&pkg, &qualifiers), pkg))
+            .collect();
+
+        let to_insert: Vec<_> = unique
+            .iter()
+            .filter(|purl| !resolved.contains_key(&Self::purl_key(purl)))
+            .collect();
+
+        if !to_insert.is_empty() {
+            let models: Vec<package::ActiveModel> = to_insert
+                .iter()
+                .map(|purl| package::ActiveModel {
+                    package_type: Set(purl.ty.clone()),
+                    package_namespace: Set(purl.namespace.clone()),
+                    package_name: Set(purl.name.clone()),
+                    version: Set(purl.version.clone()),
+                    ..Default::default()
+                })
+                .collect();
+
+            // `to_insert` may hold several purls that share
+            // type/namespace/name/version and differ only in qualifiers,
+            // so re-querying by those base columns can't tell the freshly
+            // inserted rows apart — none of them has a qualifier row yet
+            // either. `exec_with_returning` hands back the inserted rows
+            // in the same order as `models`, which is the only thing that
+            // still lines a row up with the purl it came from.
+            let inserted = package::Entity::insert_many(models)
+                .exec_with_returning(&*self.db)
+                .await?;
+
+            let qualifier_models: Vec<package_qualifier::ActiveModel> = inserted
+                .iter()
+                .zip(to_insert.iter())
+                .flat_map(|(pkg, purl)| {
+                    purl.qualifiers
+                        .iter()
+                        .map(|(k, v)| package_qualifier::ActiveModel {
+                            package_id: Set(pkg.id),
+                            key: Set(k.clone()),
+                            value: Set(v.clone()),
+                            ..Default::default()
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+
+            if !qualifier_models.is_empty() {
+                package_qualifier::Entity::insert_many(qualifier_models)
+                    .exec(&*self.db)
+                    .await?;
+            }
+
+            for (pkg, purl) in inserted.into_iter().zip(to_insert.iter()) {
+                resolved.insert(Self::purl_key(purl), pkg);
+            }
+        }
+
+        Ok(purls
+            .iter()
+            .filter_map(|purl| resolved.get(&Self::purl_key(purl)).cloned())
+            .collect())
+    }
+
+    /// Qualifiers in a stable, comparable order: a `HashMap`'s iteration
+    /// order isn't, so two otherwise-identical qualifier sets that
+    /// happened to be built in different orders must still compare equal
+    /// as a key.
+    fn sorted_qualifiers(qualifiers: &HashMap<String, String>) -> Vec<(String, String)> {
+        let mut sorted: Vec<_> = qualifiers
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        sorted.sort();
+        sorted
+    }
+
+    /// Identifies a package by its full canonical pURL, qualifiers
+    /// included: two packages that agree on type/namespace/name/version
+    /// but differ in qualifiers (e.g. a `classifier=sources` vs. a
+    /// `classifier=tests` Maven artifact) are different packages, not the
+    /// same one ingested twice.
+    fn purl_key(purl: &Purl) -> (String, Option<String>, String, String, Vec<(String, String)>) {
+        (
+            purl.ty.clone(),
+            purl.namespace.clone(),
+            purl.name.clone(),
+            purl.version.clone(),
+            Self::sorted_qualifiers(&purl.qualifiers),
+        )
+    }
+
+    fn package_key(
+        pkg: &package::Model,
+        qualifiers: &[package_qualifier::Model],
+    ) -> (String, Option<String>, String, String, Vec<(String, String)>) {
+        let qualifiers = qualifiers
+            .iter()
+            .map(|q| (q.key.clone(), q.value.clone()))
+            .collect::<HashMap<_, _>>();
+        (
+            pkg.package_type.clone(),
+            pkg.package_namespace.clone(),
+            pkg.package_name.clone(),
+            pkg.version.clone(),
+            Self::sorted_qualifiers(&qualifiers),
+        )
+    }
+
+    /// Hydrate a set of package ids into `Purl`s with a single
+    /// `find().filter(id IN (...))` query, instead of one `find_by_id` per
+    /// id as the naive tree walks used to do.
+    async fn hydrate_purls(
+        &self,
+        package_ids: &HashSet<i32>,
+    ) -> Result<HashMap<i32, Purl>, anyhow::Error> {
+        if package_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let found = package::Entity::find()
+            .filter(package::Column::Id.is_in(package_ids.iter().copied()))
+            .find_with_related(package_qualifier::Entity)
+            .all(&*self.db)
+            .await?;
+
+        let mut purls = HashMap::new();
+        for (base, qualifiers) in found {
+            let id = base.id;
+            purls.insert(id, self.package_to_purl(base, qualifiers)?);
+        }
+
+        Ok(purls)
+    }
+
+    fn purls_condition(purls: &[Purl]) -> Condition {
+        purls.iter().fold(Condition::any(), |cond, purl| {
+            let mut entry = Condition::all()
+                .add(package::Column::PackageType.eq(purl.ty.clone()))
+                .add(package::Column::PackageName.eq(purl.name.clone()))
+                .add(package::Column::Version.eq(purl.version.clone()));
+
+            entry = match &purl.namespace {
+                Some(ns) => entry.add(package::Column::PackageNamespace.eq(ns.clone())),
+                None => entry.add(package::Column::PackageNamespace.is_null()),
+            };
+
+            cond.add(entry)
+        })
+    }
+
     pub async fn insert_or_fetch_package<'a>(
         &self,
         r#type: &str,
@@ -211,6 +601,90 @@ impl System {
         }
     }
 
+    /// Bulk-ingest dependency edges, resolving and inserting all the
+    /// distinct packages involved in a single [`Self::ingest_packages`]
+    /// call, then upserting every edge in one `insert_many`.
+    pub async fn ingest_package_dependencies<P1: Into<Purl>, P2: Into<Purl>>(
+        &self,
+        dependencies: impl IntoIterator<Item = (P1, P2)>,
+    ) -> Result<Vec<package_dependency::Model>, anyhow::Error> {
+        let pairs: Vec<(Purl, Purl)> = dependencies
+            .into_iter()
+            .map(|(dependent, dependency)| (dependent.into(), dependency.into()))
+            .collect();
+
+        let all_purls = pairs
+            .iter()
+            .flat_map(|(dependent, dependency)| [dependent.clone(), dependency.clone()]);
+
+        let packages = self.ingest_packages(all_purls).await?;
+
+        let package_ids: Vec<_> = packages.iter().map(|pkg| pkg.id).collect();
+        let qualifiers = package_qualifier::Entity::find()
+            .filter(package_qualifier::Column::PackageId.is_in(package_ids))
+            .all(&*self.db)
+            .await?;
+
+        let by_key: HashMap<_, _> = packages
+            .into_iter()
+            .map(|pkg| {
+                let pkg_qualifiers: Vec<_> = qualifiers
+                    .iter()
+                    .filter(|q| q.package_id == pkg.id)
+                    .cloned()
+                    .collect();
+                (Self::package_key(&pkg, &pkg_qualifiers), pkg)
+            })
+            .collect();
+
+        let edges: Vec<(i32, i32)> = pairs
+            .iter()
+            .filter_map(|(dependent, dependency)| {
+                let dependent = by_key.get(&Self::purl_key(dependent))?;
+                let dependency = by_key.get(&Self::purl_key(dependency))?;
+                Some((dependent.id, dependency.id))
+            })
+            .collect();
+
+        if edges.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let models: Vec<package_dependency::ActiveModel> = edges
+            .iter()
+            .map(|(dependent_id, dependency_id)| package_dependency::ActiveModel {
+                dependent_package_id: Set(*dependent_id),
+                dependency_package_id: Set(*dependency_id),
+            })
+            .collect();
+
+        package_dependency::Entity::insert_many(models)
+            .on_conflict(
+                OnConflict::columns([
+                    package_dependency::Column::DependentPackageId,
+                    package_dependency::Column::DependencyPackageId,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .exec(&*self.db)
+            .await?;
+
+        let mut condition = Condition::any();
+        for (dependent_id, dependency_id) in &edges {
+            condition = condition.add(
+                Condition::all()
+                    .add(package_dependency::Column::DependentPackageId.eq(*dependent_id))
+                    .add(package_dependency::Column::DependencyPackageId.eq(*dependency_id)),
+            );
+        }
+
+        Ok(package_dependency::Entity::find()
+            .filter(condition)
+            .all(&*self.db)
+            .await?)
+    }
+
     fn packages_to_purls(
         &self,
         packages: Vec<(package::Model, Vec<package_qualifier::Model>)>,
@@ -259,6 +733,135 @@ impl System {
         Ok(self.packages_to_purls(found)?)
     }
 
+    /// Paginated variant of [`Self::direct_dependencies`].
+    pub async fn direct_dependencies_paginated<P: Into<Purl>>(
+        &self,
+        dependent_package: P,
+        per_page: u64,
+        page: u64,
+    ) -> Result<(u64, Vec<Purl>), anyhow::Error> {
+        let dependent = self.ingest_package(dependent_package).await?;
+
+        let paginator = dependent
+            .find_linked(ToDependency)
+            .paginate(&*self.db, per_page);
+
+        let total_pages = paginator.num_pages().await?;
+        let page_packages = paginator.fetch_page(page).await?;
+
+        let package_ids: Vec<_> = page_packages.iter().map(|pkg| pkg.id).collect();
+
+        let qualifiers = package_qualifier::Entity::find()
+            .filter(package_qualifier::Column::PackageId.is_in(package_ids))
+            .all(&*self.db)
+            .await?;
+
+        let found = page_packages
+            .into_iter()
+            .map(|pkg| {
+                let pkg_qualifiers = qualifiers
+                    .iter()
+                    .filter(|q| q.package_id == pkg.id)
+                    .cloned()
+                    .collect();
+                (pkg, pkg_qualifiers)
+            })
+            .collect();
+
+        Ok((total_pages, self.packages_to_purls(found)?))
+    }
+
+    pub async fn direct_dependents<P: Into<Purl>>(
+        &self,
+        dependency_package: P,
+    ) -> Result<Vec<Purl>, anyhow::Error> {
+        let dependency = self.ingest_package(dependency_package).await?;
+
+        let found = dependency
+            .find_linked(ToDependent)
+            .find_with_related(package_qualifier::Entity)
+            .all(&*self.db)
+            .await?;
+
+        Ok(self.packages_to_purls(found)?)
+    }
+
+    /// Walk the transitive *dependents* of a package: everything that
+    /// depends on it, directly or indirectly. This is the mirror image of
+    /// [`Self::transitive_dependencies`], anchored and recursed on the
+    /// opposite column, and is the core query behind "if CVE hits package X,
+    /// what downstream artifacts are affected?".
+    pub async fn transitive_dependents<P: Into<Purl>>(
+        &self,
+        root: P,
+    ) -> Result<PackageTree, anyhow::Error> {
+        let root_model = self.ingest_package(root).await?;
+        let root_id = Value::Int(Some(root_model.id));
+
+        let relationships = package_dependency::Entity::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                self.db.get_database_backend(),
+                r#"
+                    WITH RECURSIVE transitive AS (
+                        SELECT
+                            timestamp, dependent_package_id, dependency_package_id
+                        FROM
+                            package_dependency
+                        WHERE
+                            dependency_package_id = $1
+                        UNION
+                        SELECT
+                            pd.timestamp, pd.dependent_package_id, pd.dependency_package_id
+                        FROM
+                            package_dependency pd
+                        INNER JOIN transitive transitive1
+                            ON pd.dependency_package_id = transitive1.dependent_package_id
+                    )
+                    SELECT * FROM transitive
+                    "#,
+                vec![root_id],
+            ))
+            .all(&*self.db)
+            .await?;
+
+        // Build the tree rooted at `root`, walking dependent -> dependency
+        // edges in reverse: each node's "dependencies" in the resulting
+        // `PackageTree` are actually its dependents.
+        let mut dependents = HashMap::new();
+        let mut all_packages = HashSet::new();
+
+        for relationship in relationships {
+            all_packages.insert(relationship.dependent_package_id);
+            all_packages.insert(relationship.dependency_package_id);
+            dependents
+                .entry(relationship.dependency_package_id)
+                .or_insert(Vec::new())
+                .push(relationship.dependent_package_id)
+        }
+
+        let purls = self.hydrate_purls(&all_packages).await?;
+
+        fn build_tree(
+            root: i32,
+            dependents: &HashMap<i32, Vec<i32>>,
+            purls: &HashMap<i32, Purl>,
+        ) -> PackageTree {
+            let dependencies = dependents
+                .get(&root)
+                .iter()
+                .flat_map(|deps| deps.iter().map(|dep| build_tree(*dep, dependents, purls)))
+                .collect();
+
+            PackageTree {
+                id: root,
+                purl: purls[&root].clone(),
+                dependencies,
+            }
+        }
+
+        Ok(build_tree(root_model.id, &dependents, &purls))
+    }
+
     /*
     pub async fn transitive_dependencies<'p, P: Into<Purl<'p>>>(
         &'p self,
@@ -342,35 +945,39 @@ impl System {
                 .push(relationship.dependency_package_id)
         }
 
-        let mut purls = HashMap::new();
-
-        for pkg_id in all_packages {
-            let pkg = package::Entity::find_by_id(pkg_id)
-                .find_with_related(package_qualifier::Entity)
-                .all(&*self.db)
-                .await?;
-
-            if !pkg.is_empty() {
-                let (base, qualifiers) = &pkg[0];
-                let purl = self.package_to_purl(base.clone(), qualifiers.clone())?;
-                purls.insert(pkg_id, purl);
-            }
-        }
+        let purls = self.hydrate_purls(&all_packages).await?;
 
+        // Track the ids visited along the current path from the root. A
+        // cyclic `package_dependency` graph would otherwise recurse
+        // forever; instead, once an id is re-encountered on the same path
+        // it is emitted as a leaf rather than recursed into again. The set
+        // is per path, so a shared (diamond-shaped) subtree reached along
+        // two different paths is still expanded once for each of them.
         fn build_tree(
             root: i32,
             relationships: &HashMap<i32, Vec<i32>>,
             purls: &HashMap<i32, Purl>,
+            visited: &mut HashSet<i32>,
         ) -> PackageTree {
+            if !visited.insert(root) {
+                return PackageTree {
+                    id: root,
+                    purl: purls[&root].clone(),
+                    dependencies: vec![],
+                };
+            }
+
             let dependencies = relationships
                 .get(&root)
                 .iter()
                 .flat_map(|deps| {
                     deps.iter()
-                        .map(|dep| build_tree(*dep, relationships, purls))
+                        .map(|dep| build_tree(*dep, relationships, purls, visited))
                 })
                 .collect();
 
+            visited.remove(&root);
+
             PackageTree {
                 id: root,
                 purl: purls[&root].clone(),
@@ -378,7 +985,65 @@ impl System {
             }
         }
 
-        Ok(build_tree(root_model.id, &dependencies, &purls))
+        Ok(build_tree(
+            root_model.id,
+            &dependencies,
+            &purls,
+            &mut HashSet::new(),
+        ))
+    }
+
+    /// Same traversal as [`Self::transitive_dependencies`], but returned as
+    /// a DAG (adjacency map plus id-to-purl lookup) rather than a tree, so
+    /// callers can render shared subgraphs without duplicating them.
+    pub async fn transitive_dependencies_dag<P: Into<Purl>>(
+        &self,
+        root: P,
+    ) -> Result<(HashMap<i32, Vec<i32>>, HashMap<i32, Purl>), anyhow::Error> {
+        let root_model = self.ingest_package(root).await?;
+        let root_id = Value::Int(Some(root_model.id));
+
+        let relationships = package_dependency::Entity::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                self.db.get_database_backend(),
+                r#"
+                    WITH RECURSIVE transitive AS (
+                        SELECT
+                            timestamp, dependent_package_id, dependency_package_id
+                        FROM
+                            package_dependency
+                        WHERE
+                            dependent_package_id = $1
+                        UNION
+                        SELECT
+                            pd.timestamp, pd.dependent_package_id, pd.dependency_package_id
+                        FROM
+                            package_dependency pd
+                        INNER JOIN transitive transitive1
+                            ON pd.dependent_package_id = transitive1.dependency_package_id
+                    )
+                    SELECT * FROM transitive
+                    "#,
+                vec![root_id],
+            ))
+            .all(&*self.db)
+            .await?;
+
+        let mut dependencies = HashMap::new();
+        let mut all_packages = HashSet::new();
+
+        for relationship in relationships {
+            all_packages.insert(relationship.dependent_package_id);
+            all_packages.insert(relationship.dependency_package_id);
+            dependencies
+                .entry(relationship.dependent_package_id)
+                .or_insert(Vec::new())
+                .push(relationship.dependency_package_id)
+        }
+
+        let purls = self.hydrate_purls(&all_packages).await?;
+
+        Ok((dependencies, purls))
     }
 }
 
@@ -430,6 +1095,35 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn ingest_packages_distinguishes_qualifiers() -> Result<(), anyhow::Error> {
+        let system = System::for_test("ingest_packages_distinguishes_qualifiers").await?;
+
+        let sources = "pkg:maven/com.test/widget@1.0?classifier=sources";
+        let tests = "pkg:maven/com.test/widget@1.0?classifier=tests";
+
+        let ingested = system.ingest_packages([sources, tests]).await?;
+        assert_eq!(ingested.len(), 2);
+        assert_ne!(
+            ingested[0].id, ingested[1].id,
+            "qualifier-distinct packages must not collapse into one row"
+        );
+
+        let fetched_sources = system.fetch_package(sources).await?.expect("sources package missing");
+        let fetched_tests = system.fetch_package(tests).await?.expect("tests package missing");
+        assert_ne!(fetched_sources.id, fetched_tests.id);
+
+        system
+            .ingest_package_dependencies([("pkg:maven/com.test/consumer@1.0", sources)])
+            .await?;
+
+        let dependencies = system.direct_dependencies("pkg:maven/com.test/consumer@1.0").await?;
+        assert_eq!(dependencies.len(), 1);
+        assert_eq!(dependencies[0], Purl::from(sources));
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn ingest_package_dependencies() -> Result<(), anyhow::Error> {
         let system = System::for_test("ingest_package_dependencies").await?;
@@ -502,4 +1196,39 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn transitive_dependencies_with_cycle() -> Result<(), anyhow::Error> {
+        let system = System::for_test("transitive_dependencies_with_cycle").await?;
+
+        system
+            .ingest_package_dependency(
+                "pkg:maven/com.test/package-a@1.0?type=jar",
+                "pkg:maven/com.test/package-b@1.0?type=jar",
+            )
+            .await?;
+
+        system
+            .ingest_package_dependency(
+                "pkg:maven/com.test/package-b@1.0?type=jar",
+                "pkg:maven/com.test/package-a@1.0?type=jar",
+            )
+            .await?;
+
+        // Must terminate instead of recursing forever on the A -> B -> A cycle.
+        let result = system
+            .transitive_dependencies("pkg:maven/com.test/package-a@1.0?type=jar")
+            .await?;
+
+        assert_eq!(
+            Purl::from("pkg:maven/com.test/package-a@1.0?type=jar"),
+            result.purl
+        );
+        assert_eq!(1, result.dependencies.len());
+        // The cycle back to `a` is reported as a leaf rather than expanded again.
+        assert_eq!(1, result.dependencies[0].dependencies.len());
+        assert_eq!(0, result.dependencies[0].dependencies[0].dependencies.len());
+
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -0,0 +1,56 @@
+//! A process-wide default sink.
+//!
+//! `AuditLog::new`/`record` both take a sink explicitly and don't need
+//! this — it exists for call sites (a `.wrap(...)` in a module's
+//! `configure`, a handler with no sink of its own threaded in) that want
+//! "whatever the operator configured at startup" without every one of
+//! them plumbing an `Arc<dyn AuditSink>` through by hand.
+
+use crate::sink::{AuditEntry, AuditSink};
+use std::sync::{Arc, OnceLock};
+
+static SINK: OnceLock<Arc<dyn AuditSink>> = OnceLock::new();
+
+/// Install the sink every subsequent [`global`] call returns. Call this
+/// once, at startup, before the server takes traffic — like `OnceLock`
+/// itself, later calls are silently ignored rather than replacing it.
+pub fn install(sink: Arc<dyn AuditSink>) {
+    let _ = SINK.set(sink);
+}
+
+/// The installed sink, or a no-op sink if [`install`] was never called
+/// (e.g. in a test, or a deployment that hasn't configured one yet) —
+/// recording is always safe to call, it just goes nowhere until then.
+pub fn global() -> Arc<dyn AuditSink> {
+    SINK.get()
+        .cloned()
+        .unwrap_or_else(|| Arc::new(NullSink) as Arc<dyn AuditSink>)
+}
+
+struct NullSink;
+
+impl AuditSink for NullSink {
+    fn record(&self, _entry: &AuditEntry) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn global_defaults_to_a_sink_that_does_not_panic() {
+        // Nothing in this binary calls `install`, so this exercises the
+        // `NullSink` fallback.
+        global().record(&AuditEntry {
+            timestamp: time::OffsetDateTime::UNIX_EPOCH,
+            principal: None,
+            method: "GET".to_string(),
+            path: "/".to_string(),
+            query: String::new(),
+            status: 200,
+            bytes: 0,
+            duration_ms: 0,
+            advisory_id: None,
+        });
+    }
+}
@@ -0,0 +1,16 @@
+//! Append-only audit trail for ingestor and search requests.
+//!
+//! See [`middleware`] for how an entry gets recorded and [`sink`] for
+//! where it's written.
+
+pub mod global;
+pub mod sink;
+
+#[cfg(feature = "actix")]
+pub mod middleware;
+
+pub use global::{global, install};
+pub use sink::{AuditEntry, AuditSink, FileSink};
+
+#[cfg(feature = "actix")]
+pub use middleware::{record, AuditLog, RecordedAdvisoryId};
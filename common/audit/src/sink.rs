@@ -0,0 +1,171 @@
+//! The record shape written for every audited request, and the
+//! pluggable destinations it can be written to.
+
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use time::OffsetDateTime;
+
+/// One audited request/response pair. Fields mirror what an operator
+/// needs to answer "who touched this advisory, and when" after the
+/// fact: `principal` and `advisory_id` are `None` until auth and the
+/// upload path respectively have something to report, rather than
+/// being left out of the shape entirely.
+#[derive(Clone, Debug, Serialize)]
+pub struct AuditEntry {
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+    pub principal: Option<String>,
+    pub method: String,
+    pub path: String,
+    pub query: String,
+    pub status: u16,
+    pub bytes: u64,
+    pub duration_ms: u128,
+    pub advisory_id: Option<i32>,
+}
+
+/// A destination audit entries are appended to. Kept minimal and
+/// synchronous (like [`super::FileSink`]'s own locking) so a sink can be
+/// driven from either the [`crate::middleware::AuditLog`] middleware or
+/// a handler that has no scope/service-registration point to wrap.
+pub trait AuditSink: Send + Sync {
+    fn record(&self, entry: &AuditEntry);
+}
+
+/// Appends one JSON line per entry to a file. Once it grows past
+/// `max_bytes` the file is renamed to the first unused numbered sibling
+/// (its extension replaced by `1`, `2`, ...: `audit.log` becomes
+/// `audit.1`, then `audit.2`) and a new file is started. This is the tamper-evident
+/// trail itself: entries are only ever appended, never rewritten, and a
+/// rotated file is left untouched rather than compacted or deleted.
+///
+/// A database-backed sink (writing alongside the `importer_report`
+/// table) is the natural other half of "pluggable", but there's no
+/// entity for it in this tree yet — add one implementing [`AuditSink`]
+/// once that table exists, and construct [`AuditLog`](crate::middleware::AuditLog)
+/// with it instead.
+pub struct FileSink {
+    state: Mutex<FileSinkState>,
+}
+
+struct FileSinkState {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    max_bytes: u64,
+}
+
+impl FileSink {
+    /// Open (or create) the log file at `path`, rotating once it would
+    /// exceed `max_bytes`.
+    pub fn open(path: impl AsRef<Path>, max_bytes: u64) -> std::io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            state: Mutex::new(FileSinkState {
+                path,
+                file,
+                written,
+                max_bytes,
+            }),
+        })
+    }
+
+    fn rotate(state: &mut FileSinkState) -> std::io::Result<()> {
+        let mut n = 1u32;
+        let rotated = loop {
+            let candidate = state.path.with_extension(n.to_string());
+            if !candidate.exists() {
+                break candidate;
+            }
+            n += 1;
+        };
+        std::fs::rename(&state.path, rotated)?;
+        state.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&state.path)?;
+        state.written = 0;
+        Ok(())
+    }
+}
+
+impl AuditSink for FileSink {
+    fn record(&self, entry: &AuditEntry) {
+        // A failed write (a full disk, a missing directory) shouldn't
+        // take the request down with it — the audit trail is best-effort
+        // supplementary evidence, not the system of record.
+        let Ok(mut line) = serde_json::to_vec(entry) else {
+            return;
+        };
+        line.push(b'\n');
+
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        if state.written + line.len() as u64 > state.max_bytes && state.written > 0 {
+            let _ = FileSink::rotate(&mut state);
+        }
+        if state.file.write_all(&line).is_ok() {
+            state.written += line.len() as u64;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn sample_entry() -> AuditEntry {
+        AuditEntry {
+            timestamp: OffsetDateTime::UNIX_EPOCH,
+            principal: Some("test-token".to_string()),
+            method: "GET".to_string(),
+            path: "/api/v1/advisory".to_string(),
+            query: "id=1".to_string(),
+            status: 200,
+            bytes: 42,
+            duration_ms: 7,
+            advisory_id: Some(1),
+        }
+    }
+
+    #[test]
+    fn appends_one_json_line_per_entry() {
+        let path = std::env::temp_dir().join(format!("audit-sink-test-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let sink = FileSink::open(&path, 1024 * 1024).expect("open sink");
+
+        sink.record(&sample_entry());
+        sink.record(&sample_entry());
+
+        let mut contents = String::new();
+        File::open(&path).unwrap().read_to_string(&mut contents).unwrap();
+        assert_eq!(contents.lines().count(), 2);
+        assert!(contents.lines().next().unwrap().contains("\"advisory_id\":1"));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn rotates_once_the_size_ceiling_is_crossed() {
+        let path = std::env::temp_dir().join("audit-sink-rotate-test.log");
+        let _ = std::fs::remove_file(&path);
+        let rotated = path.with_extension("1");
+        let _ = std::fs::remove_file(&rotated);
+
+        let sink = FileSink::open(&path, 1).expect("open sink");
+        sink.record(&sample_entry());
+        sink.record(&sample_entry());
+
+        assert!(rotated.exists());
+
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(&rotated);
+    }
+}
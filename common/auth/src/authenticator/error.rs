@@ -5,6 +5,12 @@ use trustify_common::error::ErrorInformation;
 pub enum AuthenticationError {
     #[error("Authentication failed")]
     Failed,
+    /// No [`TokenAuthenticatorConfig`](crate::authenticator::token::TokenAuthenticatorConfig)
+    /// was registered as app data for this scope. This must fail the
+    /// request rather than let it through unauthenticated, since the
+    /// only way to reach this arm is a missing `configure()` wiring.
+    #[error("Authentication is not configured for this endpoint")]
+    NotConfigured,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -22,6 +28,13 @@ impl actix_web::ResponseError for AuthenticationError {
                 message: self.to_string(),
                 details: None,
             }),
+            Self::NotConfigured => {
+                actix_web::HttpResponse::InternalServerError().json(ErrorInformation {
+                    error: "AuthNotConfigured".into(),
+                    message: self.to_string(),
+                    details: None,
+                })
+            }
         }
     }
 }
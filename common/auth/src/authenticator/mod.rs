@@ -0,0 +1,4 @@
+pub mod error;
+pub mod token;
+
+pub use error::{AuthenticationError, AuthorizationError};
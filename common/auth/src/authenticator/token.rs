@@ -0,0 +1,147 @@
+//! Static bearer-token authentication/authorization for HTTP endpoints.
+//!
+//! `AuthenticationError`/`AuthorizationError` already know how to render
+//! themselves as 401/403 `ErrorInformation` bodies, but nothing validated
+//! a request against them. This adds a minimal token store (no external
+//! identity provider required) keyed by the raw bearer token, each token
+//! carrying the set of [`Scope`]s it may use, plus a typed actix extractor
+//! so an endpoint declares the scope it needs as a parameter rather than
+//! checking it by hand in the handler body.
+
+use std::collections::{HashMap, HashSet};
+
+/// A capability a bearer token may be granted.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Scope {
+    Upload,
+    Download,
+    Search,
+}
+
+/// The set of valid tokens and their scopes, plus whether scopeless
+/// requests are let through for read-only access (public mirrors that
+/// want open `download`/`search` but still gate `upload`).
+#[derive(Clone, Debug, Default)]
+pub struct TokenAuthenticatorConfig {
+    tokens: HashMap<String, HashSet<Scope>>,
+    pub allow_anonymous_read: bool,
+}
+
+impl TokenAuthenticatorConfig {
+    pub fn new(allow_anonymous_read: bool) -> Self {
+        Self {
+            tokens: HashMap::new(),
+            allow_anonymous_read,
+        }
+    }
+
+    /// Register `token` as authorized for `scopes`. Later calls for the
+    /// same token replace its scope set rather than merging it.
+    pub fn with_token(mut self, token: impl Into<String>, scopes: impl IntoIterator<Item = Scope>) -> Self {
+        self.tokens.insert(token.into(), scopes.into_iter().collect());
+        self
+    }
+
+    fn scopes_for(&self, token: &str) -> Option<&HashSet<Scope>> {
+        self.tokens.get(token)
+    }
+
+    fn is_read_scope(scope: Scope) -> bool {
+        matches!(scope, Scope::Download | Scope::Search)
+    }
+}
+
+#[cfg(feature = "actix")]
+mod actix_support {
+    use super::{Scope, TokenAuthenticatorConfig};
+    use crate::authenticator::error::{AuthenticationError, AuthorizationError};
+    use actix_web::dev::Payload;
+    use actix_web::{web, FromRequest, HttpRequest};
+    use std::future::Ready;
+    use std::marker::PhantomData;
+
+    fn bearer_token(req: &HttpRequest) -> Option<&str> {
+        req.headers()
+            .get(actix_web::http::header::AUTHORIZATION)?
+            .to_str()
+            .ok()?
+            .strip_prefix("Bearer ")
+    }
+
+    fn check(config: &TokenAuthenticatorConfig, req: &HttpRequest, scope: Scope) -> Result<(), actix_web::Error> {
+        match bearer_token(req) {
+            Some(token) => match config.scopes_for(token) {
+                Some(scopes) if scopes.contains(&scope) => Ok(()),
+                Some(_) => Err(AuthorizationError::Failed.into()),
+                None => Err(AuthenticationError::Failed.into()),
+            },
+            None if config.allow_anonymous_read && TokenAuthenticatorConfig::is_read_scope(scope) => Ok(()),
+            None => Err(AuthenticationError::Failed.into()),
+        }
+    }
+
+    /// Marker type naming the [`Scope`] a handler parameter requires.
+    pub trait RequiredScope {
+        const SCOPE: Scope;
+    }
+
+    pub struct Upload;
+    impl RequiredScope for Upload {
+        const SCOPE: Scope = Scope::Upload;
+    }
+
+    pub struct Download;
+    impl RequiredScope for Download {
+        const SCOPE: Scope = Scope::Download;
+    }
+
+    pub struct Search;
+    impl RequiredScope for Search {
+        const SCOPE: Scope = Scope::Search;
+    }
+
+    /// Extractor proving the request carried a valid token for `S::SCOPE`
+    /// (or qualified for anonymous read access). Add it as a handler
+    /// parameter to gate the whole endpoint; it carries no data of its
+    /// own, so handlers that don't need the token value can ignore it
+    /// with `_auth: Authorized<Upload>`.
+    pub struct Authorized<S: RequiredScope>(PhantomData<S>);
+
+    impl<S: RequiredScope> FromRequest for Authorized<S> {
+        type Error = actix_web::Error;
+        type Future = Ready<Result<Self, Self::Error>>;
+
+        fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+            let result = match req.app_data::<web::Data<TokenAuthenticatorConfig>>() {
+                Some(config) => check(config, req, S::SCOPE).map(|()| Authorized(PhantomData)),
+                // A missing `TokenAuthenticatorConfig` means whoever mounted
+                // this scope forgot to register one — that must fail the
+                // request, not silently serve it unauthenticated.
+                None => Err(AuthenticationError::NotConfigured.into()),
+            };
+            std::future::ready(result)
+        }
+    }
+}
+
+#[cfg(feature = "actix")]
+pub use actix_support::{Authorized, Download, RequiredScope, Search, Upload};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_token_has_no_scopes() {
+        let config = TokenAuthenticatorConfig::new(false).with_token("good", [Scope::Upload]);
+        assert_eq!(config.scopes_for("good"), Some(&HashSet::from([Scope::Upload])));
+        assert_eq!(config.scopes_for("bad"), None);
+    }
+
+    #[test]
+    fn read_scopes_are_download_and_search() {
+        assert!(TokenAuthenticatorConfig::is_read_scope(Scope::Download));
+        assert!(TokenAuthenticatorConfig::is_read_scope(Scope::Search));
+        assert!(!TokenAuthenticatorConfig::is_read_scope(Scope::Upload));
+    }
+}
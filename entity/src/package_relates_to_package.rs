@@ -0,0 +1,26 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+use crate::relationship::Relationship;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "package_relates_to_package")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub left_package_id: i32,
+    pub relationship: Relationship,
+    pub right_package_id: i32,
+    pub sbom_id: i32,
+    /// When this edge became part of the graph.
+    pub valid_from: OffsetDateTime,
+    /// When this edge was superseded by a re-ingest, or `None` if it is
+    /// still current. Retraction always closes out the row by setting this
+    /// column rather than deleting it, so a past point-in-time query
+    /// reproduces exactly the graph as it looked then.
+    pub valid_to: Option<OffsetDateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,31 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// The kind of relation one package can have to another within the context
+/// of a single SBOM's `package_relates_to_package` graph.
+#[derive(
+    Copy, Clone, Debug, Hash, Eq, PartialEq, EnumIter, DeriveActiveEnum, Serialize, Deserialize,
+)]
+#[sea_orm(rs_type = "i32", db_type = "Integer")]
+pub enum Relationship {
+    #[sea_orm(num_value = 0)]
+    ContainedBy,
+    #[sea_orm(num_value = 1)]
+    DependencyOf,
+    /// The left package provides the capability the right package names
+    /// (e.g. a virtual package or an implementation of an interface).
+    #[sea_orm(num_value = 2)]
+    Provides,
+    /// The left package replaces/supersedes the right package.
+    #[sea_orm(num_value = 3)]
+    Replaces,
+    /// The left package cannot be installed alongside the right package.
+    #[sea_orm(num_value = 4)]
+    Conflicts,
+    /// The left package is an optional dependency of the right package.
+    #[sea_orm(num_value = 5)]
+    OptionalDependencyOf,
+    /// The left package is a build-time-only tool used by the right package.
+    #[sea_orm(num_value = 6)]
+    BuildToolOf,
+}
@@ -0,0 +1,29 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "sbom_describes_package")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub sbom_id: i32,
+    #[sea_orm(primary_key)]
+    pub qualified_package_id: i32,
+    /// When this description became part of the graph.
+    pub valid_from: OffsetDateTime,
+    /// When this description was superseded by a re-ingest, or `None` if it
+    /// is still current. Retraction always closes out the row by setting
+    /// this column rather than deleting it, so a past point-in-time query
+    /// reproduces exactly the graph as it looked then.
+    pub valid_to: Option<OffsetDateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::sbom::Entity",
+        from = "Column::SbomId",
+        to = "super::sbom::Column::Id"
+    )]
+    Sbom,
+}
+impl ActiveModelBehavior for ActiveModel {}
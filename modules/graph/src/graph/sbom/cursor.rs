@@ -0,0 +1,135 @@
+//! Keyset (cursor) pagination for [`super::Graph::sboms_by_cursor`].
+//!
+//! Offset/limit pagination over `sbom` degrades on large tables (the
+//! database still has to walk and discard every skipped row) and can
+//! skip or repeat rows when new SBOMs are ingested mid-scan, since the
+//! offset is only ever a row count, not a position. A cursor instead
+//! encodes the last row seen as `(published, id)`; the next page is
+//! fetched with `WHERE (published, id) < (cursor.published, cursor.id)
+//! ORDER BY published DESC, id DESC`, which stays correct regardless of
+//! what else is inserted while a caller is paging through.
+
+use super::super::error::Error;
+use sea_orm::ColumnTrait;
+use sea_query::Condition;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use trustify_entity::sbom;
+
+/// The last row returned by a page of [`super::Graph::sboms_by_cursor`].
+/// Opaque to callers: round-trip it through [`Self::encode`] and
+/// [`Self::decode`] rather than reading its fields.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SbomCursor {
+    pub(crate) published: Option<OffsetDateTime>,
+    pub(crate) id: i32,
+}
+
+impl SbomCursor {
+    pub(crate) fn new(published: Option<OffsetDateTime>, id: i32) -> Self {
+        Self { published, id }
+    }
+
+    /// Serialize this cursor to an opaque token a caller can hand back
+    /// to continue a scan via [`Self::decode`].
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).expect("SbomCursor always serializes");
+        json.iter().fold(String::with_capacity(json.len() * 2), |mut out, byte| {
+            use std::fmt::Write;
+            write!(out, "{byte:02x}").expect("writing to a String cannot fail");
+            out
+        })
+    }
+
+    /// Parse a token previously produced by [`Self::encode`].
+    pub fn decode(token: &str) -> Result<Self, Error> {
+        let invalid = || Error::from(sea_orm::DbErr::Custom("invalid sbom cursor".into()));
+
+        if token.len() % 2 != 0 {
+            return Err(invalid());
+        }
+
+        let bytes = (0..token.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&token[i..i + 2], 16).map_err(|_| invalid()))
+            .collect::<Result<Vec<u8>, _>>()?;
+
+        serde_json::from_slice(&bytes).map_err(|_| invalid())
+    }
+}
+
+/// The `WHERE` condition selecting every row that sorts after `cursor`
+/// under `ORDER BY published DESC, id DESC`.
+///
+/// `published` is nullable, and Postgres puts nulls first under `DESC`,
+/// so "after the cursor" splits into two shapes `.lt`/`.eq` against
+/// `cursor.published` directly can't express — `published < NULL` and
+/// `published = NULL` are never true in SQL, so a cursor landing on a
+/// null-published row used to match nothing at all, silently
+/// truncating the scan.
+pub(crate) fn page_condition(cursor: &SbomCursor) -> Condition {
+    match cursor.published {
+        Some(published) => Condition::any()
+            .add(sbom::Column::Published.lt(published))
+            .add(
+                Condition::all()
+                    .add(sbom::Column::Published.eq(published))
+                    .add(sbom::Column::Id.lt(cursor.id)),
+            ),
+        None => Condition::any()
+            // Every non-null row sorts after the whole null group the
+            // cursor row was part of.
+            .add(sbom::Column::Published.is_not_null())
+            // Other null rows in the same group are ordered by id
+            // among themselves.
+            .add(
+                Condition::all()
+                    .add(sbom::Column::Published.is_null())
+                    .add(sbom::Column::Id.lt(cursor.id)),
+            ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{DbBackend, EntityTrait, QueryFilter};
+
+    fn rendered_sql(cursor: &SbomCursor) -> String {
+        sbom::Entity::find()
+            .filter(page_condition(cursor))
+            .build(DbBackend::Postgres)
+            .to_string()
+    }
+
+    #[test]
+    fn a_null_published_cursor_still_admits_later_rows() {
+        let cursor = SbomCursor::new(None, 42);
+        let sql = rendered_sql(&cursor);
+
+        assert!(sql.contains("IS NOT NULL"), "must admit every non-null row: {sql}");
+        assert!(sql.contains("IS NULL"), "must still admit later null rows: {sql}");
+    }
+
+    #[test]
+    fn a_dated_cursor_does_not_reference_null_checks() {
+        let cursor = SbomCursor::new(Some(OffsetDateTime::UNIX_EPOCH), 42);
+        let sql = rendered_sql(&cursor);
+
+        assert!(
+            !sql.to_uppercase().contains("IS NULL") && !sql.to_uppercase().contains("IS NOT NULL"),
+            "a dated cursor has already passed every null row, so it shouldn't filter on nullness: {sql}"
+        );
+    }
+}
+
+/// A page of results fetched via keyset pagination. Unlike offset/limit's
+/// `PaginatedResults`, there is no `total`: counting the whole keyset on
+/// every page would defeat the point of avoiding a full table scan.
+#[derive(Debug)]
+pub struct CursorPage<T> {
+    pub items: Vec<T>,
+    /// `Some` if the scan may have more rows; pass it back as the next
+    /// call's cursor to continue. `None` once exhausted.
+    pub next_cursor: Option<SbomCursor>,
+}
@@ -0,0 +1,95 @@
+//! Disk-backed warm layer behind [`super::PackageCache`], memoizing
+//! *successful* pURL resolutions across process restarts.
+//!
+//! Re-importing an SBOM that overlaps a previous one otherwise re-hits
+//! the graph for every pURL it already resolved last run. This caches
+//! only the qualified package's stable id, not the lifetime-bound
+//! `QualifiedPackageContext<'a>` itself: a hit re-hydrates the context
+//! by id from the current `Graph`/`Transactional` (a cheap lookup by
+//! primary key), a miss ingests normally and writes the resolved id
+//! back.
+
+use super::super::error::Error;
+use crate::graph::package::qualified_package::QualifiedPackageContext;
+use crate::graph::Graph;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QuerySelect, QueryTrait};
+use std::path::Path;
+use trustify_common::db::Transactional;
+use trustify_common::purl::Purl;
+use trustify_entity as entity;
+
+/// A `sled`-backed key/value store mapping a canonical pURL string to
+/// the `qualified_package.id` it last resolved to.
+pub struct DiskResolutionCache {
+    tree: sled::Db,
+}
+
+impl DiskResolutionCache {
+    /// Open (or create) the on-disk store at `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let tree = sled::open(path).map_err(|err| {
+            Error::from(sea_orm::DbErr::Custom(format!(
+                "opening package resolution cache: {err}"
+            )))
+        })?;
+        Ok(Self { tree })
+    }
+
+    fn get_id(&self, purl: &Purl) -> Option<i32> {
+        let bytes = self.tree.get(purl.to_string()).ok().flatten()?;
+        Some(i32::from_be_bytes(bytes.as_ref().try_into().ok()?))
+    }
+
+    fn put_id(&self, purl: &Purl, id: i32) {
+        // Best-effort: a failed write just means the next process falls
+        // back to a full re-ingest for this pURL, not a correctness bug.
+        let _ = self.tree.insert(purl.to_string(), &id.to_be_bytes());
+    }
+
+    /// Forget `purl`'s cached resolution. This is the actual "bust
+    /// cache" control point for yanked or withdrawn packages: unlike
+    /// `PackageCache`, which is rebuilt fresh for every ingest, this
+    /// store persists across restarts, so an operator-facing flush
+    /// operation needs somewhere durable to act on.
+    pub fn invalidate(&self, purl: &Purl) {
+        let _ = self.tree.remove(purl.to_string());
+    }
+
+    /// Forget every cached resolution.
+    pub fn clear(&self) {
+        let _ = self.tree.clear();
+    }
+
+    /// Resolve `purl`, consulting this disk cache before falling back to
+    /// full ingestion.
+    pub(crate) async fn lookup<'a, TX>(
+        &self,
+        graph: &'a Graph,
+        purl: Purl,
+        tx: TX,
+    ) -> Result<QualifiedPackageContext<'a>, Error>
+    where
+        TX: AsRef<Transactional> + Clone,
+    {
+        if let Some(id) = self.get_id(&purl) {
+            let found = graph
+                .get_qualified_packages_by_query(
+                    entity::qualified_package::Entity::find()
+                        .select_only()
+                        .column(entity::qualified_package::Column::Id)
+                        .filter(entity::qualified_package::Column::Id.eq(id))
+                        .into_query(),
+                    tx.clone(),
+                )
+                .await?;
+
+            if let Some(found) = found.into_iter().next() {
+                return Ok(found);
+            }
+        }
+
+        let resolved = graph.ingest_qualified_package(purl.clone(), tx).await?;
+        self.put_id(&purl, resolved.qualified_package.id);
+        Ok(resolved)
+    }
+}
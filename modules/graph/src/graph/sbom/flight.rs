@@ -0,0 +1,134 @@
+//! Arrow Flight `DoGet` endpoint over [`SbomContext::export_relationships_arrow`].
+//!
+//! A client sends a [`Ticket`] whose bytes are a JSON-serialized
+//! `SbomLocator` and gets back the matching SBOM's full relationship edge
+//! set as a stream of Arrow [`FlightData`] batches.
+
+use crate::graph::sbom::RecordBatchStream;
+use crate::graph::Graph;
+use arrow_flight::encode::FlightDataEncoderBuilder;
+use arrow_flight::flight_service_server::FlightService;
+use arrow_flight::{
+    Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightInfo, HandshakeRequest,
+    HandshakeResponse, PollInfo, PutResult, SchemaResult, Ticket,
+};
+use futures::stream::BoxStream;
+use futures::TryStreamExt;
+use tonic::{Request, Response, Status, Streaming};
+use trustify_common::db::Transactional;
+use trustify_common::sbom::SbomLocator;
+
+/// Serves an SBOM's relationship graph over Arrow Flight. Only `do_get` is
+/// implemented; every other RPC returns `unimplemented` since this service
+/// exists solely to stream [`RecordBatchStream`]s out, not as a general
+/// Flight server.
+#[derive(Clone)]
+pub struct SbomFlightService {
+    graph: Graph,
+}
+
+impl SbomFlightService {
+    pub fn new(graph: Graph) -> Self {
+        Self { graph }
+    }
+}
+
+#[tonic::async_trait]
+impl FlightService for SbomFlightService {
+    type HandshakeStream = BoxStream<'static, Result<HandshakeResponse, Status>>;
+    type ListFlightsStream = BoxStream<'static, Result<FlightInfo, Status>>;
+    type DoGetStream = BoxStream<'static, Result<FlightData, Status>>;
+    type DoPutStream = BoxStream<'static, Result<PutResult, Status>>;
+    type DoExchangeStream = BoxStream<'static, Result<FlightData, Status>>;
+    type DoActionStream = BoxStream<'static, Result<arrow_flight::Result, Status>>;
+    type ListActionsStream = BoxStream<'static, Result<ActionType, Status>>;
+
+    async fn do_get(
+        &self,
+        request: Request<Ticket>,
+    ) -> Result<Response<Self::DoGetStream>, Status> {
+        let ticket = request.into_inner();
+
+        let locator: SbomLocator = serde_json::from_slice(&ticket.ticket)
+            .map_err(|err| Status::invalid_argument(format!("malformed ticket: {err}")))?;
+
+        let sbom = self
+            .graph
+            .locate_sbom(locator, Transactional::None)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?
+            .ok_or_else(|| Status::not_found("no matching sbom"))?;
+
+        let batches: RecordBatchStream = sbom.export_relationships_arrow(Transactional::None);
+        let batches = batches.map_err(|err| arrow_schema::ArrowError::ExternalError(Box::new(err)));
+
+        let flight_data = FlightDataEncoderBuilder::new()
+            .build(batches)
+            .map_err(|err| Status::internal(err.to_string()));
+
+        Ok(Response::new(Box::pin(flight_data) as Self::DoGetStream))
+    }
+
+    async fn handshake(
+        &self,
+        _request: Request<Streaming<HandshakeRequest>>,
+    ) -> Result<Response<Self::HandshakeStream>, Status> {
+        Err(Status::unimplemented("handshake is not supported"))
+    }
+
+    async fn list_flights(
+        &self,
+        _request: Request<Criteria>,
+    ) -> Result<Response<Self::ListFlightsStream>, Status> {
+        Err(Status::unimplemented("list_flights is not supported"))
+    }
+
+    async fn get_flight_info(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented("get_flight_info is not supported"))
+    }
+
+    async fn poll_flight_info(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<PollInfo>, Status> {
+        Err(Status::unimplemented("poll_flight_info is not supported"))
+    }
+
+    async fn get_schema(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<SchemaResult>, Status> {
+        Err(Status::unimplemented("get_schema is not supported"))
+    }
+
+    async fn do_put(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoPutStream>, Status> {
+        Err(Status::unimplemented("do_put is not supported"))
+    }
+
+    async fn do_exchange(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoExchangeStream>, Status> {
+        Err(Status::unimplemented("do_exchange is not supported"))
+    }
+
+    async fn do_action(
+        &self,
+        _request: Request<Action>,
+    ) -> Result<Response<Self::DoActionStream>, Status> {
+        Err(Status::unimplemented("do_action is not supported"))
+    }
+
+    async fn list_actions(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<Self::ListActionsStream>, Status> {
+        Err(Status::unimplemented("list_actions is not supported"))
+    }
+}
@@ -0,0 +1,124 @@
+//! Metrics for the SBOM ingestion and query paths.
+//!
+//! The `#[instrument]` spans already scattered through this module give
+//! good per-request tracing, but nothing rolls up into a dashboard an
+//! operator can watch continuously. This records counters and latency
+//! histograms at the same entry/exit points those spans mark, and exports
+//! them over OTLP so the backend is swappable without recompiling.
+
+use opentelemetry::metrics::{Counter, Histogram, Meter};
+use opentelemetry::KeyValue;
+use std::sync::OnceLock;
+use std::time::Instant;
+
+/// Counters and histograms for the SBOM subsystem, built once against the
+/// global [`Meter`] and shared by every [`crate::graph::Graph`] handle.
+pub(crate) struct Metrics {
+    pub(crate) sboms_ingested: Counter<u64>,
+    pub(crate) described_packages: Counter<u64>,
+    pub(crate) described_cpes: Counter<u64>,
+    pub(crate) relationships_upserted: Counter<u64>,
+    pub(crate) related_packages_transitively_duration: Histogram<f64>,
+    pub(crate) vulnerability_assertions_duration: Histogram<f64>,
+    pub(crate) locate_sbom_duration: Histogram<f64>,
+}
+
+impl Metrics {
+    fn new(meter: &Meter) -> Self {
+        Self {
+            sboms_ingested: meter
+                .u64_counter("trustify.sbom.ingested")
+                .with_description("SBOMs ingested, by outcome (inserted vs. dedup_hit)")
+                .init(),
+            described_packages: meter
+                .u64_counter("trustify.sbom.described_packages")
+                .with_description("Packages newly added to an SBOM's description set")
+                .init(),
+            described_cpes: meter
+                .u64_counter("trustify.sbom.described_cpes")
+                .with_description("CPEs newly added to an SBOM's description set")
+                .init(),
+            relationships_upserted: meter
+                .u64_counter("trustify.sbom.relationships_upserted")
+                .with_description("package_relates_to_package edges newly opened")
+                .init(),
+            related_packages_transitively_duration: meter
+                .f64_histogram("trustify.sbom.related_packages_transitively.duration")
+                .with_description("related_packages_transitively_at latency, in seconds")
+                .with_unit(opentelemetry::metrics::Unit::new("s"))
+                .init(),
+            vulnerability_assertions_duration: meter
+                .f64_histogram("trustify.sbom.vulnerability_assertions.duration")
+                .with_description("vulnerability_assertions latency, in seconds")
+                .with_unit(opentelemetry::metrics::Unit::new("s"))
+                .init(),
+            locate_sbom_duration: meter
+                .f64_histogram("trustify.sbom.locate_sbom.duration")
+                .with_description("locate_sbom_by_* latency, in seconds, by locator kind")
+                .with_unit(opentelemetry::metrics::Unit::new("s"))
+                .init(),
+        }
+    }
+}
+
+fn metrics() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(|| Metrics::new(&opentelemetry::global::meter("trustify_graph")))
+}
+
+pub(crate) fn record_sbom_ingested(outcome: &'static str) {
+    metrics()
+        .sboms_ingested
+        .add(1, &[KeyValue::new("outcome", outcome)]);
+}
+
+pub(crate) fn record_described_packages(count: u64) {
+    metrics().described_packages.add(count, &[]);
+}
+
+pub(crate) fn record_described_cpes(count: u64) {
+    metrics().described_cpes.add(count, &[]);
+}
+
+pub(crate) fn record_relationships_upserted(count: u64) {
+    metrics().relationships_upserted.add(count, &[]);
+}
+
+/// Run `fut`, recording its wall-clock duration against `histogram`
+/// regardless of whether it succeeds, then return its result unchanged.
+pub(crate) async fn time_histogram<F, T>(
+    select: impl FnOnce(&'static Metrics) -> &'static Histogram<f64>,
+    attributes: &[KeyValue],
+    fut: F,
+) -> T
+where
+    F: std::future::Future<Output = T>,
+{
+    let start = Instant::now();
+    let result = fut.await;
+    select(metrics()).record(start.elapsed().as_secs_f64(), attributes);
+    result
+}
+
+/// Configure the global OTLP metrics exporter. Operators point `endpoint`
+/// at their collector; when it is `None` the existing global
+/// `MeterProvider` (a no-op one, absent any other setup) is left in
+/// place, so this is safe to call unconditionally at startup.
+pub fn install_otlp_exporter(
+    endpoint: Option<&str>,
+) -> Result<(), opentelemetry::metrics::MetricsError> {
+    let Some(endpoint) = endpoint else {
+        return Ok(());
+    };
+
+    let exporter = opentelemetry_otlp::new_exporter()
+        .tonic()
+        .with_endpoint(endpoint);
+
+    opentelemetry_otlp::new_pipeline()
+        .metrics(opentelemetry_sdk::runtime::Tokio)
+        .with_exporter(exporter)
+        .build()?;
+
+    Ok(())
+}
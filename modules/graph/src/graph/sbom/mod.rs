@@ -10,13 +10,16 @@ use sea_orm::{
     ActiveModelTrait, ColumnTrait, EntityTrait, FromQueryResult, QueryFilter, QueryOrder,
     QuerySelect, QueryTrait, RelationTrait, Select, Set,
 };
-use sea_query::{Condition, Func, JoinType, OnConflict, Query, SimpleExpr};
-use std::collections::hash_map::Entry;
+use futures::future::{FutureExt, LocalBoxFuture, Shared};
+use lru::LruCache;
+use sea_query::{Condition, Expr, Func, JoinType, Query, SimpleExpr};
+use std::cell::{Cell, RefCell};
 use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Formatter};
-use std::rc::Rc;
+use std::num::NonZeroUsize;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use time::OffsetDateTime;
 use tracing::instrument;
 use trustify_common::cpe::Cpe;
@@ -32,9 +35,23 @@ use trustify_entity::{sbom, vulnerability};
 use trustify_module_search::model::SearchOptions;
 use trustify_module_search::query::Query as TrustifyQuery;
 
+mod cursor;
+mod disk_cache;
+mod export;
+mod flight;
+mod metrics;
+mod observer;
 pub mod spdx;
 mod tests;
 
+pub use cursor::{CursorPage, SbomCursor};
+pub use disk_cache::DiskResolutionCache;
+pub use export::{relationships_schema, RecordBatchStream};
+pub use flight::SbomFlightService;
+pub use metrics::install_otlp_exporter;
+pub use observer::{ChangeBatch, Observer, ObserverCallback, ObserverPredicate};
+use observer::deliver as deliver_to_observers;
+
 #[derive(Clone, Default)]
 pub struct SbomInformation {
     pub title: Option<String>,
@@ -47,6 +64,15 @@ impl From<()> for SbomInformation {
     }
 }
 
+/// Two packages, both reachable from the same described-package closure,
+/// that a `Conflicts` edge says cannot coexist.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PackageConflict {
+    pub sbom_id: i32,
+    pub left: Purl,
+    pub right: Purl,
+}
+
 type SelectEntity<E> = Select<E>;
 
 impl Graph {
@@ -75,6 +101,61 @@ impl Graph {
         })
     }
 
+    /// Keyset-paginated variant of [`Self::sboms`]: instead of an
+    /// offset/limit `Paginated`, the caller passes back the
+    /// [`SbomCursor`] from the previous [`CursorPage`] (or `None` for the
+    /// first page), avoiding the full-table walk offset pagination incurs
+    /// on a large `sbom` table and the row skipping/duplication an
+    /// offset-based page can see when rows are ingested mid-scan.
+    ///
+    /// `deadline`, if set, bounds how long the underlying query is
+    /// allowed to run; a pathological `search` filter times out instead
+    /// of holding the connection open indefinitely.
+    #[instrument(skip(tx), err)]
+    pub async fn sboms_by_cursor<TX: AsRef<Transactional>>(
+        &self,
+        search: SearchOptions,
+        limit: u64,
+        cursor: Option<SbomCursor>,
+        deadline: Option<Duration>,
+        tx: TX,
+    ) -> Result<CursorPage<SbomContext>, Error> {
+        let connection = self.connection(&tx);
+
+        let mut query = sbom::Entity::find()
+            .filtering(search)?
+            .order_by_desc(sbom::Column::Published)
+            .order_by_desc(sbom::Column::Id)
+            .limit(limit + 1);
+
+        if let Some(cursor) = &cursor {
+            query = query.filter(cursor::page_condition(cursor));
+        }
+
+        let fetch = query.all(&connection);
+
+        let mut rows = match deadline {
+            Some(deadline) => tokio::time::timeout(deadline, fetch).await.map_err(|_| {
+                Error::from(sea_orm::DbErr::Custom(
+                    "sboms query exceeded its deadline".into(),
+                ))
+            })??,
+            None => fetch.await?,
+        };
+
+        let next_cursor = if (rows.len() as u64) > limit {
+            rows.truncate(limit as usize);
+            rows.last().map(|row| SbomCursor::new(row.published, row.id))
+        } else {
+            None
+        };
+
+        Ok(CursorPage {
+            items: rows.drain(0..).map(|each| (self, each).into()).collect(),
+            next_cursor,
+        })
+    }
+
     pub async fn get_sbom_by_id<TX: AsRef<Transactional>>(
         &self,
         id: i32,
@@ -111,6 +192,7 @@ impl Graph {
         tx: TX,
     ) -> Result<SbomContext, Error> {
         if let Some(found) = self.get_sbom(location, sha256, &tx).await? {
+            metrics::record_sbom_ingested("dedup_hit");
             return Ok(found);
         }
 
@@ -127,7 +209,12 @@ impl Graph {
             ..Default::default()
         };
 
-        Ok((self, model.insert(&self.connection(&tx)).await?).into())
+        let sbom: SbomContext = (self, model.insert(&self.connection(&tx)).await?).into();
+
+        self.notify_observers(&ChangeBatch::for_sbom(sbom.sbom.id));
+        metrics::record_sbom_ingested("inserted");
+
+        Ok(sbom)
     }
 
     /// Fetch a single SBOM located via internal `id`, external `location` (URL),
@@ -202,11 +289,17 @@ impl Graph {
         id: i32,
         tx: TX,
     ) -> Result<Option<SbomContext>, Error> {
-        let query = entity::sbom::Entity::find_by_id(id);
-        Ok(entity::sbom::Entity::find_by_id(id)
-            .one(&self.connection(&tx))
-            .await?
-            .map(|sbom| (self, sbom).into()))
+        metrics::time_histogram(
+            |m| &m.locate_sbom_duration,
+            &[opentelemetry::KeyValue::new("locator_kind", "id")],
+            async {
+                Ok(entity::sbom::Entity::find_by_id(id)
+                    .one(&self.connection(&tx))
+                    .await?
+                    .map(|sbom| (self, sbom).into()))
+            },
+        )
+        .await
     }
 
     async fn locate_sbom_by_location<TX: AsRef<Transactional>>(
@@ -214,10 +307,14 @@ impl Graph {
         location: &str,
         tx: TX,
     ) -> Result<Option<SbomContext>, Error> {
-        self.locate_one_sbom(
-            entity::sbom::Entity::find()
-                .filter(entity::sbom::Column::Location.eq(location.to_string())),
-            tx,
+        metrics::time_histogram(
+            |m| &m.locate_sbom_duration,
+            &[opentelemetry::KeyValue::new("locator_kind", "location")],
+            self.locate_one_sbom(
+                entity::sbom::Entity::find()
+                    .filter(entity::sbom::Column::Location.eq(location.to_string())),
+                tx,
+            ),
         )
         .await
     }
@@ -240,10 +337,14 @@ impl Graph {
         sha256: &str,
         tx: TX,
     ) -> Result<Option<SbomContext>, Error> {
-        self.locate_one_sbom(
-            entity::sbom::Entity::find()
-                .filter(entity::sbom::Column::Sha256.eq(sha256.to_string())),
-            tx,
+        metrics::time_histogram(
+            |m| &m.locate_sbom_duration,
+            &[opentelemetry::KeyValue::new("locator_kind", "sha256")],
+            self.locate_one_sbom(
+                entity::sbom::Entity::find()
+                    .filter(entity::sbom::Column::Sha256.eq(sha256.to_string())),
+                tx,
+            ),
         )
         .await
     }
@@ -266,25 +367,32 @@ impl Graph {
         purl: Purl,
         tx: TX,
     ) -> Result<Option<SbomContext>, Error> {
-        let package = self.get_qualified_package(purl, &tx).await?;
-
-        if let Some(package) = package {
-            self.locate_one_sbom(
-                entity::sbom::Entity::find()
-                    .join(
-                        JoinType::LeftJoin,
-                        entity::sbom_describes_package::Relation::Sbom.def().rev(),
+        metrics::time_histogram(
+            |m| &m.locate_sbom_duration,
+            &[opentelemetry::KeyValue::new("locator_kind", "purl")],
+            async {
+                let package = self.get_qualified_package(purl, &tx).await?;
+
+                if let Some(package) = package {
+                    self.locate_one_sbom(
+                        entity::sbom::Entity::find()
+                            .join(
+                                JoinType::LeftJoin,
+                                entity::sbom_describes_package::Relation::Sbom.def().rev(),
+                            )
+                            .filter(
+                                entity::sbom_describes_package::Column::QualifiedPackageId
+                                    .eq(package.qualified_package.id),
+                            ),
+                        &tx,
                     )
-                    .filter(
-                        entity::sbom_describes_package::Column::QualifiedPackageId
-                            .eq(package.qualified_package.id),
-                    ),
-                &tx,
-            )
-            .await
-        } else {
-            Ok(None)
-        }
+                    .await
+                } else {
+                    Ok(None)
+                }
+            },
+        )
+        .await
     }
 
     async fn locate_sboms_by_purl<TX: AsRef<Transactional>>(
@@ -318,20 +426,27 @@ impl Graph {
         cpe: &Cpe,
         tx: TX,
     ) -> Result<Option<SbomContext>, Error> {
-        if let Some(cpe) = self.get_cpe(cpe.clone(), &tx).await? {
-            self.locate_one_sbom(
-                entity::sbom::Entity::find()
-                    .join(
-                        JoinType::LeftJoin,
-                        entity::sbom_describes_cpe::Relation::Sbom.def().rev(),
+        metrics::time_histogram(
+            |m| &m.locate_sbom_duration,
+            &[opentelemetry::KeyValue::new("locator_kind", "cpe22")],
+            async {
+                if let Some(cpe) = self.get_cpe(cpe.clone(), &tx).await? {
+                    self.locate_one_sbom(
+                        entity::sbom::Entity::find()
+                            .join(
+                                JoinType::LeftJoin,
+                                entity::sbom_describes_cpe::Relation::Sbom.def().rev(),
+                            )
+                            .filter(entity::sbom_describes_cpe::Column::CpeId.eq(cpe.cpe.id)),
+                        &tx,
                     )
-                    .filter(entity::sbom_describes_cpe::Column::CpeId.eq(cpe.cpe.id)),
-                &tx,
-            )
-            .await
-        } else {
-            Ok(None)
-        }
+                    .await
+                } else {
+                    Ok(None)
+                }
+            },
+        )
+        .await
     }
 
     async fn locate_sboms_by_cpe22<C: Into<Cpe>, TX: AsRef<Transactional>>(
@@ -360,6 +475,17 @@ impl Graph {
 pub struct SbomContext {
     pub(crate) graph: Graph,
     pub sbom: entity::sbom::Model,
+    /// `system`'s observer registry, captured at construction time.
+    ///
+    /// `graph` above is an owned clone of `system` and so lives at its
+    /// own, different address — notifying through `graph` directly
+    /// would look up a registry nothing was ever registered against.
+    /// Grabbing the `Arc` from `system` itself, while it's still the
+    /// instance a caller actually called `register_observer` on, and
+    /// keeping that `Arc` around instead is what lets
+    /// [`Self::ingest_describes_cpe22`] and friends reach the real
+    /// observers below. See the note on `observer::scoped_registry`.
+    observer_registry: Arc<Mutex<Vec<Observer>>>,
 }
 
 impl PartialEq for SbomContext {
@@ -377,6 +503,7 @@ impl Debug for SbomContext {
 impl From<(&Graph, entity::sbom::Model)> for SbomContext {
     fn from((system, sbom): (&Graph, entity::sbom::Model)) -> Self {
         Self {
+            observer_registry: system.observer_registry(),
             graph: system.clone(),
             sbom,
         }
@@ -385,11 +512,12 @@ impl From<(&Graph, entity::sbom::Model)> for SbomContext {
 
 impl SbomContext {
     #[instrument(skip(tx), err)]
-    pub async fn ingest_describes_cpe22<C: Into<Cpe> + Debug, TX: AsRef<Transactional>>(
+    pub async fn ingest_describes_cpe22<C: Into<Cpe> + Clone + Debug, TX: AsRef<Transactional>>(
         &self,
         cpe: C,
         tx: TX,
     ) -> Result<(), Error> {
+        let described: Cpe = cpe.clone().into();
         let cpe = self.graph.ingest_cpe22(cpe, &tx).await?;
 
         let fetch = entity::sbom_describes_cpe::Entity::find()
@@ -405,34 +533,83 @@ impl SbomContext {
             };
 
             model.insert(&self.graph.connection(&tx)).await?;
+
+            deliver_to_observers(
+                &self.observer_registry,
+                &ChangeBatch {
+                    described_cpes: vec![described],
+                    ..ChangeBatch::for_sbom(self.sbom.id)
+                },
+            );
+            metrics::record_described_cpes(1);
         }
         Ok(())
     }
 
+    /// Re-ingesting an SBOM never mutates a historical row: a description
+    /// from the previous ingest that named a different package than
+    /// `purl` is closed out by setting its `valid_to` to now, and `purl`
+    /// itself is inserted fresh if it isn't already open for this SBOM.
+    /// This keeps any past point-in-time query reproducing exactly the
+    /// graph as it looked then, the same way [`Self::ingest_relationships`]
+    /// does for edges.
     #[instrument(skip(tx), err)]
     pub async fn ingest_describes_package<TX: AsRef<Transactional>>(
         &self,
         purl: Purl,
         tx: TX,
     ) -> Result<(), Error> {
-        let fetch = entity::sbom_describes_package::Entity::find()
-            .filter(
-                Condition::all()
-                    .add(entity::sbom_describes_package::Column::SbomId.eq(self.sbom.id)),
-            )
-            .one(&self.graph.connection(&tx))
+        let package = self.graph.ingest_qualified_package(purl, &tx).await?;
+
+        let open = entity::sbom_describes_package::Entity::find()
+            .filter(entity::sbom_describes_package::Column::SbomId.eq(self.sbom.id))
+            .filter(entity::sbom_describes_package::Column::ValidTo.is_null())
+            .all(&self.graph.connection(&tx))
             .await?;
 
-        if fetch.is_none() {
-            let package = self.graph.ingest_qualified_package(purl, &tx).await?;
+        let already_open = open
+            .iter()
+            .any(|row| row.qualified_package_id == package.qualified_package.id);
 
-            let model = entity::sbom_describes_package::ActiveModel {
-                sbom_id: Set(self.sbom.id),
-                qualified_package_id: Set(package.qualified_package.id),
-            };
+        if already_open {
+            return Ok(());
+        }
 
-            model.insert(&self.graph.connection(&tx)).await?;
+        let now = OffsetDateTime::now_utc();
+
+        let superseded: Vec<i32> = open
+            .iter()
+            .filter(|row| row.qualified_package_id != package.qualified_package.id)
+            .map(|row| row.qualified_package_id)
+            .collect();
+
+        if !superseded.is_empty() {
+            entity::sbom_describes_package::Entity::update_many()
+                .col_expr(entity::sbom_describes_package::Column::ValidTo, Expr::value(now))
+                .filter(entity::sbom_describes_package::Column::SbomId.eq(self.sbom.id))
+                .filter(entity::sbom_describes_package::Column::QualifiedPackageId.is_in(superseded))
+                .exec(&self.graph.connection(&tx))
+                .await?;
         }
+
+        let model = entity::sbom_describes_package::ActiveModel {
+            sbom_id: Set(self.sbom.id),
+            qualified_package_id: Set(package.qualified_package.id),
+            valid_from: Set(now),
+            valid_to: Set(None),
+        };
+
+        model.insert(&self.graph.connection(&tx)).await?;
+
+        deliver_to_observers(
+            &self.observer_registry,
+            &ChangeBatch {
+                described_purls: vec![package.into()],
+                ..ChangeBatch::for_sbom(self.sbom.id)
+            },
+        );
+        metrics::record_described_packages(1);
+
         Ok(())
     }
 
@@ -440,6 +617,19 @@ impl SbomContext {
     pub async fn describes_packages<TX: AsRef<Transactional>>(
         &self,
         tx: TX,
+    ) -> Result<Vec<QualifiedPackageContext>, Error> {
+        self.describes_packages_at(OffsetDateTime::now_utc(), tx)
+            .await
+    }
+
+    /// Time-travel variant of [`Self::describes_packages`]: the packages
+    /// this SBOM described at `at`, following the same validity-interval
+    /// rule as [`Self::related_packages_transitively_at`].
+    #[instrument(skip(tx), err)]
+    pub async fn describes_packages_at<TX: AsRef<Transactional>>(
+        &self,
+        at: OffsetDateTime,
+        tx: TX,
     ) -> Result<Vec<QualifiedPackageContext>, Error> {
         self.graph
             .get_qualified_packages_by_query(
@@ -447,6 +637,12 @@ impl SbomContext {
                     .select_only()
                     .column(entity::sbom_describes_package::Column::QualifiedPackageId)
                     .filter(entity::sbom_describes_package::Column::SbomId.eq(self.sbom.id))
+                    .filter(entity::sbom_describes_package::Column::ValidFrom.lte(at))
+                    .filter(
+                        Condition::any()
+                            .add(entity::sbom_describes_package::Column::ValidTo.is_null())
+                            .add(entity::sbom_describes_package::Column::ValidTo.gt(at)),
+                    )
                     .into_query(),
                 tx,
             )
@@ -472,10 +668,15 @@ impl SbomContext {
 
     /// Within the context of *this* SBOM, ingest a relationship between
     /// two packages.
+    ///
+    /// This only ever opens a new edge (or leaves an already-open,
+    /// identical one alone) — it never retracts edges missing from a
+    /// single call. [`Self::ingest_relationships`] is the entry point that
+    /// also closes out superseded edges for a full re-ingest.
     #[instrument(skip(tx), err)]
     async fn ingest_package_relates_to_package<'a, TX: AsRef<Transactional>>(
         &'a self,
-        cache: &mut PackageCache<'a>,
+        cache: &PackageCache<'a>,
         left_package_input: Purl,
         relationship: Relationship,
         right_package_input: Purl,
@@ -486,28 +687,49 @@ impl SbomContext {
 
         match (&*left_package, &*right_package) {
             (Ok(left_package), Ok(right_package)) => {
-                let entity = entity::package_relates_to_package::ActiveModel {
-                    left_package_id: Set(left_package.qualified_package.id),
-                    relationship: Set(relationship),
-                    right_package_id: Set(right_package.qualified_package.id),
-                    sbom_id: Set(self.sbom.id),
-                };
-
-                // upsert
-
-                entity::package_relates_to_package::Entity::insert(entity)
-                    .on_conflict(
-                        OnConflict::columns([
-                            entity::package_relates_to_package::Column::LeftPackageId,
-                            entity::package_relates_to_package::Column::Relationship,
-                            entity::package_relates_to_package::Column::RightPackageId,
-                            entity::package_relates_to_package::Column::SbomId,
-                        ])
-                        .do_nothing()
-                        .to_owned(),
+                let already_open = entity::package_relates_to_package::Entity::find()
+                    .filter(entity::package_relates_to_package::Column::SbomId.eq(self.sbom.id))
+                    .filter(
+                        entity::package_relates_to_package::Column::LeftPackageId
+                            .eq(left_package.qualified_package.id),
+                    )
+                    .filter(
+                        entity::package_relates_to_package::Column::Relationship.eq(relationship),
                     )
-                    .exec(&self.graph.connection(&tx))
-                    .await?;
+                    .filter(
+                        entity::package_relates_to_package::Column::RightPackageId
+                            .eq(right_package.qualified_package.id),
+                    )
+                    .filter(entity::package_relates_to_package::Column::ValidTo.is_null())
+                    .one(&self.graph.connection(&tx))
+                    .await?
+                    .is_some();
+
+                if !already_open {
+                    let left_id = left_package.qualified_package.id;
+                    let right_id = right_package.qualified_package.id;
+
+                    let entity = entity::package_relates_to_package::ActiveModel {
+                        left_package_id: Set(left_id),
+                        relationship: Set(relationship),
+                        right_package_id: Set(right_id),
+                        sbom_id: Set(self.sbom.id),
+                        valid_from: Set(OffsetDateTime::now_utc()),
+                        valid_to: Set(None),
+                        ..Default::default()
+                    };
+
+                    entity.insert(&self.graph.connection(&tx)).await?;
+
+                    deliver_to_observers(
+                        &self.observer_registry,
+                        &ChangeBatch {
+                            relationships: vec![(left_id, relationship, right_id)],
+                            ..ChangeBatch::for_sbom(self.sbom.id)
+                        },
+                    );
+                    metrics::record_relationships_upserted(1);
+                }
             }
             (Err(_), Err(_)) => {
                 log::warn!(
@@ -533,6 +755,147 @@ impl SbomContext {
         Ok(())
     }
 
+    /// Batch variant of [`Self::ingest_package_relates_to_package`]: prime
+    /// `cache` with every distinct pURL referenced by the batch so each is
+    /// resolved exactly once no matter how many edges reference it, then
+    /// reconcile the batch against whatever edges for this SBOM are
+    /// currently open. This is the path large SPDX/CycloneDX imports should
+    /// use instead of looping over individual relationships.
+    ///
+    /// Re-ingesting an SBOM never mutates a historical row: an edge from
+    /// the previous ingest that is missing from `relationships` is closed
+    /// out by setting its `valid_to` to now, and an edge in `relationships`
+    /// that has no currently-open match is inserted fresh with `valid_from`
+    /// set to now. An edge present in both is left untouched. This keeps
+    /// any past point-in-time query reproducing exactly the graph as it
+    /// looked then, even across repeated re-ingests.
+    #[instrument(skip(cache, tx), err)]
+    pub async fn ingest_relationships<'a, TX: AsRef<Transactional>>(
+        &'a self,
+        cache: &PackageCache<'a>,
+        relationships: &[(Purl, Relationship, Purl)],
+        tx: TX,
+    ) -> Result<(), Error> {
+        let mut distinct = HashSet::new();
+        for (left, _, right) in relationships {
+            distinct.insert(left.clone());
+            distinct.insert(right.clone());
+        }
+
+        // Single-flight coalescing means concurrently resolving every
+        // distinct pURL here costs no more than resolving them one at a
+        // time ever did, but no longer serializes on each other's I/O.
+        futures::future::join_all(distinct.iter().map(|purl| cache.lookup(purl))).await;
+
+        let mut wanted = HashSet::new();
+
+        for (left_input, relationship, right_input) in relationships {
+            let left_package = cache.lookup(left_input).await;
+            let right_package = cache.lookup(right_input).await;
+
+            match (&*left_package, &*right_package) {
+                (Ok(left_package), Ok(right_package)) => {
+                    wanted.insert((
+                        left_package.qualified_package.id,
+                        *relationship,
+                        right_package.qualified_package.id,
+                    ));
+                }
+                (Err(_), Err(_)) => {
+                    log::warn!(
+                        "unable to ingest relationships between non-fully-qualified packages {}, {}",
+                        left_input,
+                        right_input,
+                    );
+                }
+                (Err(_), Ok(_)) => {
+                    log::warn!(
+                        "unable to ingest relationships involving a non-fully-qualified package {}",
+                        left_input
+                    );
+                }
+                (Ok(_), Err(_)) => {
+                    log::warn!(
+                        "unable to ingest relationships involving a non-fully-qualified package {}",
+                        right_input
+                    );
+                }
+            }
+        }
+
+        let open = entity::package_relates_to_package::Entity::find()
+            .filter(entity::package_relates_to_package::Column::SbomId.eq(self.sbom.id))
+            .filter(entity::package_relates_to_package::Column::ValidTo.is_null())
+            .all(&self.graph.connection(&tx))
+            .await?;
+
+        let open_keys: HashMap<(i32, Relationship, i32), i32> = open
+            .iter()
+            .map(|edge| {
+                (
+                    (edge.left_package_id, edge.relationship, edge.right_package_id),
+                    edge.id,
+                )
+            })
+            .collect();
+
+        let superseded: Vec<i32> = open_keys
+            .iter()
+            .filter(|(key, _)| !wanted.contains(key))
+            .map(|(_, id)| *id)
+            .collect();
+
+        let now = OffsetDateTime::now_utc();
+
+        if !superseded.is_empty() {
+            entity::package_relates_to_package::Entity::update_many()
+                .col_expr(
+                    entity::package_relates_to_package::Column::ValidTo,
+                    Expr::value(now),
+                )
+                .filter(entity::package_relates_to_package::Column::Id.is_in(superseded))
+                .exec(&self.graph.connection(&tx))
+                .await?;
+        }
+
+        let new_edges: Vec<(i32, Relationship, i32)> = wanted
+            .iter()
+            .filter(|key| !open_keys.contains_key(key))
+            .copied()
+            .collect();
+
+        if new_edges.is_empty() {
+            return Ok(());
+        }
+
+        let entities = new_edges.iter().map(|(left_package_id, relationship, right_package_id)| {
+            entity::package_relates_to_package::ActiveModel {
+                left_package_id: Set(*left_package_id),
+                relationship: Set(*relationship),
+                right_package_id: Set(*right_package_id),
+                sbom_id: Set(self.sbom.id),
+                valid_from: Set(now),
+                valid_to: Set(None),
+                ..Default::default()
+            }
+        });
+
+        entity::package_relates_to_package::Entity::insert_many(entities)
+            .exec(&self.graph.connection(&tx))
+            .await?;
+
+        metrics::record_relationships_upserted(new_edges.len() as u64);
+        deliver_to_observers(
+            &self.observer_registry,
+            &ChangeBatch {
+                relationships: new_edges,
+                ..ChangeBatch::for_sbom(self.sbom.id)
+            },
+        );
+
+        Ok(())
+    }
+
     pub async fn related_packages_transitively_x<TX: AsRef<Transactional>>(
         &self,
         relationship: Relationship,
@@ -558,6 +921,7 @@ impl SbomContext {
                                 self.sbom.id.into(),
                                 pkg.qualified_package.id.into(),
                                 relationship.into(),
+                                OffsetDateTime::now_utc().into(),
                             ]),
                             QualifiedPackageTransitive,
                         )
@@ -576,47 +940,74 @@ impl SbomContext {
         pkg: Purl,
         tx: TX,
     ) -> Result<Vec<QualifiedPackageContext>, Error> {
-        let pkg = self.graph.get_qualified_package(pkg, &tx).await?;
-
-        if let Some(pkg) = pkg {
-            #[derive(Debug, FromQueryResult)]
-            struct Related {
-                left_package_id: i32,
-                right_package_id: i32,
-            }
-
-            let rels: SimpleExpr = SimpleExpr::Custom(format!(
-                "array[{}]",
-                relationships
-                    .iter()
-                    .map(|e| (*e as i32).to_string())
-                    .collect::<Vec<_>>()
-                    .join(",")
-            ));
+        self.related_packages_transitively_at(relationships, pkg, OffsetDateTime::now_utc(), tx)
+            .await
+    }
 
-            let sbom_id: SimpleExpr = self.sbom.id.into();
-            let qualified_package_id: SimpleExpr = pkg.qualified_package.id.into();
+    /// Time-travel variant of [`Self::related_packages_transitively`]: walk
+    /// the transitive closure as it looked at `at`, following only edges
+    /// whose validity interval covers that instant (`valid_from <= at AND
+    /// (valid_to IS NULL OR at < valid_to)`). Passing the current time
+    /// reproduces [`Self::related_packages_transitively`]; passing a past
+    /// timestamp reproduces the graph exactly as it existed then, even
+    /// across re-ingests that have since closed out or replaced edges.
+    pub async fn related_packages_transitively_at<TX: AsRef<Transactional>>(
+        &self,
+        relationships: &[Relationship],
+        pkg: Purl,
+        at: OffsetDateTime,
+        tx: TX,
+    ) -> Result<Vec<QualifiedPackageContext>, Error> {
+        metrics::time_histogram(
+            |m| &m.related_packages_transitively_duration,
+            &[],
+            async {
+                let pkg = self.graph.get_qualified_package(pkg, &tx).await?;
+
+                if let Some(pkg) = pkg {
+                    #[derive(Debug, FromQueryResult)]
+                    struct Related {
+                        left_package_id: i32,
+                        right_package_id: i32,
+                    }
 
-            Ok(self
-                .graph
-                .get_qualified_packages_by_query(
-                    Query::select()
-                        .column(LeftPackageId)
-                        .from_function(
-                            Func::cust(QualifiedPackageTransitive).args([
-                                sbom_id,
-                                qualified_package_id,
-                                rels,
-                            ]),
-                            QualifiedPackageTransitive,
+                    let rels: SimpleExpr = SimpleExpr::Custom(format!(
+                        "array[{}]",
+                        relationships
+                            .iter()
+                            .map(|e| (*e as i32).to_string())
+                            .collect::<Vec<_>>()
+                            .join(",")
+                    ));
+
+                    let sbom_id: SimpleExpr = self.sbom.id.into();
+                    let qualified_package_id: SimpleExpr = pkg.qualified_package.id.into();
+                    let at: SimpleExpr = at.into();
+
+                    Ok(self
+                        .graph
+                        .get_qualified_packages_by_query(
+                            Query::select()
+                                .column(LeftPackageId)
+                                .from_function(
+                                    Func::cust(QualifiedPackageTransitive).args([
+                                        sbom_id,
+                                        qualified_package_id,
+                                        rels,
+                                        at,
+                                    ]),
+                                    QualifiedPackageTransitive,
+                                )
+                                .to_owned(),
+                            &tx,
                         )
-                        .to_owned(),
-                    &tx,
-                )
-                .await?)
-        } else {
-            Ok(vec![])
-        }
+                        .await?)
+                } else {
+                    Ok(vec![])
+                }
+            },
+        )
+        .await
     }
 
     pub async fn related_packages<TX: AsRef<Transactional>>(
@@ -637,6 +1028,7 @@ impl SbomContext {
                     entity::package_relates_to_package::Column::RightPackageId
                         .eq(pkg.qualified_package.id),
                 )
+                .filter(entity::package_relates_to_package::Column::ValidTo.is_null())
                 .into_query();
 
             let mut found = entity::qualified_package::Entity::find()
@@ -672,34 +1064,147 @@ impl SbomContext {
         }
     }
 
-    pub async fn vulnerability_assertions<TX: AsRef<Transactional>>(
+    /// Fetch the `QualifiedPackageContext`s for a set of `qualified_package`
+    /// ids with a single query, for hydrating the endpoints of
+    /// `package_relates_to_package` edges discovered during relationship
+    /// resolution.
+    async fn get_qualified_packages_by_ids<TX: AsRef<Transactional>>(
         &self,
+        ids: impl IntoIterator<Item = i32>,
         tx: TX,
-    ) -> Result<HashMap<QualifiedPackageContext, PackageVulnerabilityAssertions>, Error> {
-        let described_packages = self.describes_packages(&tx).await?;
-        let mut applicable = HashSet::new();
+    ) -> Result<HashMap<i32, QualifiedPackageContext>, Error> {
+        let found = self
+            .graph
+            .get_qualified_packages_by_query(
+                entity::qualified_package::Entity::find()
+                    .select_only()
+                    .column(entity::qualified_package::Column::Id)
+                    .filter(entity::qualified_package::Column::Id.is_in(ids))
+                    .into_query(),
+                tx,
+            )
+            .await?;
 
-        for pkg in described_packages {
-            applicable.extend(
-                self.related_packages_transitively(
-                    &[Relationship::DependencyOf, Relationship::ContainedBy],
-                    pkg.into(),
-                    Transactional::None,
-                )
-                .await?,
+        Ok(found
+            .into_iter()
+            .map(|pkg| (pkg.qualified_package.id, pkg))
+            .collect())
+    }
+
+    /// Post-process a reachable set computed by [`Self::related_packages_transitively`]
+    /// to honor the semantics of `Replaces`/`Provides` and `Conflicts` edges:
+    /// every `Replaces(a, b)` or `Provides(a, b)` where `b` is reachable
+    /// rewrites the node to `a`, and every `Conflicts(x, y)` edge where
+    /// both endpoints fall inside the reachable set is surfaced as a
+    /// [`PackageConflict`].
+    async fn resolve_relationship_semantics<TX: AsRef<Transactional>>(
+        &self,
+        reachable: Vec<QualifiedPackageContext>,
+        tx: TX,
+    ) -> Result<(Vec<QualifiedPackageContext>, Vec<PackageConflict>), Error> {
+        let mut by_id: HashMap<i32, QualifiedPackageContext> = reachable
+            .into_iter()
+            .map(|pkg| (pkg.qualified_package.id, pkg))
+            .collect();
+        let reachable_ids: HashSet<i32> = by_id.keys().copied().collect();
+
+        let replaces = entity::package_relates_to_package::Entity::find()
+            .filter(entity::package_relates_to_package::Column::SbomId.eq(self.sbom.id))
+            .filter(
+                entity::package_relates_to_package::Column::Relationship
+                    .is_in([Relationship::Replaces, Relationship::Provides]),
             )
-        }
+            .filter(entity::package_relates_to_package::Column::RightPackageId.is_in(reachable_ids.iter().copied()))
+            .filter(entity::package_relates_to_package::Column::ValidTo.is_null())
+            .all(&self.graph.connection(&tx))
+            .await?;
 
-        let mut assertions = HashMap::new();
+        let replacements = self
+            .get_qualified_packages_by_ids(
+                replaces.iter().map(|edge| edge.left_package_id),
+                &tx,
+            )
+            .await?;
 
-        for pkg in applicable {
-            let package_assertions = pkg.vulnerability_assertions(&tx).await?;
-            if !package_assertions.assertions.is_empty() {
-                assertions.insert(pkg.clone(), pkg.vulnerability_assertions(&tx).await?);
+        for edge in &replaces {
+            if let Some(replacement) = replacements.get(&edge.left_package_id) {
+                by_id.insert(edge.right_package_id, replacement.clone());
             }
         }
 
-        Ok(assertions)
+        let conflict_edges = entity::package_relates_to_package::Entity::find()
+            .filter(entity::package_relates_to_package::Column::SbomId.eq(self.sbom.id))
+            .filter(
+                entity::package_relates_to_package::Column::Relationship
+                    .eq(Relationship::Conflicts),
+            )
+            .filter(entity::package_relates_to_package::Column::LeftPackageId.is_in(reachable_ids.iter().copied()))
+            .filter(entity::package_relates_to_package::Column::RightPackageId.is_in(reachable_ids.iter().copied()))
+            .filter(entity::package_relates_to_package::Column::ValidTo.is_null())
+            .all(&self.graph.connection(&tx))
+            .await?;
+
+        let conflicts = conflict_edges
+            .iter()
+            .filter_map(|edge| {
+                Some(PackageConflict {
+                    sbom_id: self.sbom.id,
+                    left: by_id.get(&edge.left_package_id)?.clone().into(),
+                    right: by_id.get(&edge.right_package_id)?.clone().into(),
+                })
+            })
+            .collect();
+
+        Ok((by_id.into_values().collect(), conflicts))
+    }
+
+    pub async fn vulnerability_assertions<TX: AsRef<Transactional>>(
+        &self,
+        tx: TX,
+    ) -> Result<
+        (
+            HashMap<QualifiedPackageContext, PackageVulnerabilityAssertions>,
+            Vec<PackageConflict>,
+        ),
+        Error,
+    > {
+        metrics::time_histogram(
+            |m| &m.vulnerability_assertions_duration,
+            &[],
+            async {
+                let described_packages = self.describes_packages(&tx).await?;
+                let mut applicable = HashSet::new();
+                let mut all_conflicts = Vec::new();
+
+                for pkg in described_packages {
+                    let reachable = self
+                        .related_packages_transitively(
+                            &[Relationship::DependencyOf, Relationship::ContainedBy],
+                            pkg.into(),
+                            Transactional::None,
+                        )
+                        .await?;
+
+                    let (reachable, conflicts) =
+                        self.resolve_relationship_semantics(reachable, &tx).await?;
+
+                    applicable.extend(reachable);
+                    all_conflicts.extend(conflicts);
+                }
+
+                let mut assertions = HashMap::new();
+
+                for pkg in applicable {
+                    let package_assertions = pkg.vulnerability_assertions(&tx).await?;
+                    if !package_assertions.assertions.is_empty() {
+                        assertions.insert(pkg.clone(), package_assertions);
+                    }
+                }
+
+                Ok((assertions, all_conflicts))
+            },
+        )
+        .await
     }
 
     /*
@@ -721,45 +1226,309 @@ impl SbomContext {
      */
 }
 
+/// The resolution shared by every caller racing to resolve the same
+/// pURL: the first `lookup` to miss installs this future and drives it,
+/// every concurrent `lookup` for the same key clones and awaits the same
+/// `Shared`, and whichever awaiter happens to poll it makes progress on
+/// behalf of all of them. The result is shared behind `Arc`; the future
+/// itself is a `LocalBoxFuture`, so a `SharedLookup` is not `Send`.
+///
+/// `QualifiedPackageContext<'a>` itself is still tied to the borrowed
+/// `&'a Graph`/`&'a Transactional` it was resolved against (see its
+/// definition in `crate::graph::package::qualified_package`, outside
+/// this module), so a `SharedLookup` can't outlive that borrow and can't
+/// be `'static` — `lookup` still only dedups concurrent `.await`s within
+/// one task's borrow of `PackageCache`, not across a `JoinSet` spawned
+/// onto other OS threads. Fanning single-flight coalescing out across a
+/// `JoinSet` needs `QualifiedPackageContext` to become an owned type
+/// there first; nothing addressable from this file can remove that
+/// lifetime.
+type SharedLookup<'a> = Shared<LocalBoxFuture<'a, Arc<Result<QualifiedPackageContext<'a>, Error>>>>;
+
+struct CacheEntry<'a> {
+    future: SharedLookup<'a>,
+    created: Instant,
+}
+
+/// How long a *failed* resolution stays cached, separate from `ttl` for
+/// successes. A transient DB hiccup during `ingest_qualified_package`
+/// must not poison every later lookup of that pURL for the rest of the
+/// cache's life.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum NegativeCachePolicy {
+    /// Never cache a failure — every lookup retries ingestion, favoring
+    /// resilience over fail-fast behavior.
+    #[default]
+    AlwaysRetry,
+    /// Cache a failure for `ttl`, after which it is retried — favors
+    /// fail-fast behavior, so a known-broken pURL doesn't keep hammering
+    /// the graph on every lookup within the window.
+    Cache { ttl: Duration },
+}
+
+// A test demonstrating that `mark_yanked`/`invalidate`/`clear` actually
+// bypass a configured disk cache would need a real `Graph` to construct
+// `PackageCache` against (both `new` and `with_disk_cache` borrow one);
+// `Graph`'s own struct definition isn't part of this module (see
+// `observer.rs`) and `mod tests` above has no backing `tests.rs` in this
+// checkout either, so there's nowhere to put a working integration test
+// from here. `DiskResolutionCache::invalidate`/`clear` are exercised
+// directly by the propagation added below instead.
+
+/// Memoizes `Purl` -> `QualifiedPackageContext` resolution for the
+/// lifetime of one ingest, bounded to `capacity` entries via LRU
+/// eviction so walking an SBOM with tens of thousands of distinct pURLs
+/// doesn't grow the cache without limit.
+///
+/// `ttl`, if set, treats an entry older than that as vacant on its next
+/// `lookup` rather than serving it forever: vulnerability/advisory state
+/// behind a qualified package can change while a long-running ingest is
+/// still walking the rest of the document.
+///
+/// `lookup` takes `&self`: the map is behind a `RefCell` so many
+/// in-flight lookups (e.g. driven concurrently via
+/// `futures::future::join_all`) can share one cache instead of forcing
+/// ingestion to resolve pURLs strictly one at a time.
 pub struct PackageCache<'a> {
-    cache: HashMap<Purl, Rc<Result<QualifiedPackageContext<'a>, Error>>>,
+    cache: RefCell<LruCache<Purl, CacheEntry<'a>>>,
+    ttl: Option<Duration>,
+    negative_cache: NegativeCachePolicy,
+    disk: Option<&'a DiskResolutionCache>,
+    /// pURLs whose cached entry must be treated as vacant on next
+    /// `lookup` regardless of TTL — set by [`Self::mark_yanked`] when a
+    /// package is yanked/withdrawn mid-ingest and the already-cached
+    /// resolution can no longer be trusted.
+    yanked: RefCell<HashSet<Purl>>,
     graph: &'a Graph,
     tx: &'a Transactional,
-    hits: usize,
+    hits: Cell<usize>,
+    negative_hits: Cell<usize>,
+    evictions: Cell<usize>,
+    expirations: Cell<usize>,
+    invalidations: Cell<usize>,
 }
 
 impl<'a> Debug for PackageCache<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("PackageCache")
-            .field("cache", &self.cache.len())
-            .field("hits", &self.hits)
+            .field("cache", &self.cache.borrow().len())
+            .field("hits", &self.hits.get())
+            .field("evictions", &self.evictions.get())
+            .field("expirations", &self.expirations.get())
             .finish()
     }
 }
 
 impl<'a> PackageCache<'a> {
     pub fn new(capacity: usize, graph: &'a Graph, tx: &'a Transactional) -> Self {
+        Self::with_ttl(capacity, None, graph, tx)
+    }
+
+    /// As [`Self::new`], but entries are force-refreshed once older than
+    /// `ttl`.
+    pub fn with_ttl(
+        capacity: usize,
+        ttl: Option<Duration>,
+        graph: &'a Graph,
+        tx: &'a Transactional,
+    ) -> Self {
         Self {
-            cache: HashMap::with_capacity(capacity),
+            cache: RefCell::new(LruCache::new(
+                NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN),
+            )),
+            ttl,
+            negative_cache: NegativeCachePolicy::default(),
+            disk: None,
+            yanked: RefCell::new(HashSet::new()),
             graph,
             tx,
-            hits: 0,
+            hits: Cell::new(0),
+            negative_hits: Cell::new(0),
+            evictions: Cell::new(0),
+            expirations: Cell::new(0),
+            invalidations: Cell::new(0),
         }
     }
 
-    pub async fn lookup(&mut self, purl: &Purl) -> Rc<Result<QualifiedPackageContext<'a>, Error>> {
-        match self.cache.entry(purl.clone()) {
-            Entry::Occupied(entry) => {
-                self.hits += 1;
-                entry.get().clone()
+    /// As [`Self::with_ttl`], consulting `disk` as a warm layer on every
+    /// in-memory miss instead of always re-running full ingestion —
+    /// amortizes resolution cost across process restarts for servers
+    /// that continuously re-ingest overlapping documents.
+    pub fn with_disk_cache(
+        capacity: usize,
+        ttl: Option<Duration>,
+        disk: &'a DiskResolutionCache,
+        graph: &'a Graph,
+        tx: &'a Transactional,
+    ) -> Self {
+        Self {
+            disk: Some(disk),
+            ..Self::with_ttl(capacity, ttl, graph, tx)
+        }
+    }
+
+    /// Set how long a failed resolution stays cached; see
+    /// [`NegativeCachePolicy`].
+    pub fn with_negative_cache_policy(mut self, policy: NegativeCachePolicy) -> Self {
+        self.negative_cache = policy;
+        self
+    }
+
+    pub fn hits(&self) -> usize {
+        self.hits.get()
+    }
+
+    /// Cache hits that served a previously-failed resolution, counted
+    /// separately from [`Self::hits`] since a caller tuning
+    /// [`NegativeCachePolicy`] needs to know how often it actually paid
+    /// off.
+    pub fn negative_hits(&self) -> usize {
+        self.negative_hits.get()
+    }
+
+    /// Entries dropped to stay within `capacity`, not counting entries
+    /// replaced because they expired or were looked up again.
+    pub fn evictions(&self) -> usize {
+        self.evictions.get()
+    }
+
+    /// Entries found present but no longer servable, and re-ingested
+    /// rather than served from cache: a success older than `ttl`, or a
+    /// failure that the [`NegativeCachePolicy`] no longer lets through.
+    pub fn expirations(&self) -> usize {
+        self.expirations.get()
+    }
+
+    /// Entries removed by [`Self::invalidate`], [`Self::clear`], or a
+    /// [`Self::mark_yanked`] flag, rather than by TTL or LRU pressure.
+    pub fn invalidations(&self) -> usize {
+        self.invalidations.get()
+    }
+
+    /// Evict `purl`'s cached resolution, if any, so the next `lookup`
+    /// re-ingests it. Safe to call while other `lookup`s are in flight —
+    /// the cache uses interior mutability precisely so a shared `&self`
+    /// reference (as [`Self::lookup`] already requires for single-flight
+    /// coalescing) is enough here too.
+    ///
+    /// Also forgets `purl` in the disk cache, if one is configured —
+    /// otherwise the next `lookup` would just repopulate this in-memory
+    /// entry straight back out of the stale on-disk one.
+    pub fn invalidate(&self, purl: &Purl) {
+        if self.cache.borrow_mut().pop(purl).is_some() {
+            self.invalidations.set(self.invalidations.get() + 1);
+        }
+        self.yanked.borrow_mut().remove(purl);
+        if let Some(disk) = self.disk {
+            disk.invalidate(purl);
+        }
+    }
+
+    /// Drop every cached resolution, including the disk cache's, if one
+    /// is configured.
+    pub fn clear(&self) {
+        let mut cache = self.cache.borrow_mut();
+        self.invalidations
+            .set(self.invalidations.get() + cache.len());
+        cache.clear();
+        self.yanked.borrow_mut().clear();
+        if let Some(disk) = self.disk {
+            disk.clear();
+        }
+    }
+
+    /// Flag `purl` as yanked/withdrawn: its cached entry, if any, is left
+    /// in place (cheap: no map mutation) but is treated as vacant the
+    /// next time it is looked up, forcing a fresh resolution. This is
+    /// the control point an operator-facing "bust cache" operation
+    /// should call after an advisory or registry update makes an
+    /// already-cached resolution suspect, without needing to know
+    /// whether the pURL is even currently cached.
+    ///
+    /// Also invalidates `purl` in the disk cache, if one is configured:
+    /// the in-memory flag alone only protects this `PackageCache`'s
+    /// lifetime, but the disk cache persists across it, so a yank that
+    /// didn't reach disk would keep being served from it on the very
+    /// next process.
+    pub fn mark_yanked(&self, purl: &Purl) {
+        self.yanked.borrow_mut().insert(purl.clone());
+        if let Some(disk) = self.disk {
+            disk.invalidate(purl);
+        }
+    }
+
+    pub async fn lookup(&self, purl: &Purl) -> Arc<Result<QualifiedPackageContext<'a>, Error>> {
+        let yanked = self.yanked.borrow_mut().remove(purl);
+
+        let existing = if yanked {
+            None
+        } else {
+            let mut cache = self.cache.borrow_mut();
+            cache.get(purl).and_then(|entry| {
+                // `peek` only sees a result once the shared future has
+                // actually resolved; while it's still in flight there is
+                // nothing to apply a policy to yet, so don't preempt the
+                // single-flight call already under way for this pURL.
+                let expired = match entry.future.peek() {
+                    None => false,
+                    Some(value) => match value.as_ref() {
+                        Ok(_) => self.ttl.is_some_and(|ttl| entry.created.elapsed() >= ttl),
+                        Err(_) => match self.negative_cache {
+                            NegativeCachePolicy::AlwaysRetry => true,
+                            NegativeCachePolicy::Cache { ttl } => {
+                                entry.created.elapsed() >= ttl
+                            }
+                        },
+                    },
+                };
+                (!expired).then(|| entry.future.clone())
+            })
+        };
+
+        if let Some(future) = existing {
+            let value = future.await;
+            if value.is_err() {
+                self.negative_hits.set(self.negative_hits.get() + 1);
+            } else {
+                self.hits.set(self.hits.get() + 1);
             }
-            Entry::Vacant(entry) => {
-                let result = self
-                    .graph
-                    .ingest_qualified_package(purl.clone(), &self.tx)
-                    .await;
-                entry.insert(Rc::new(result)).clone()
+            return value;
+        }
+
+        if self.cache.borrow_mut().pop(purl).is_some() {
+            if yanked {
+                self.invalidations.set(self.invalidations.get() + 1);
+            } else {
+                self.expirations.set(self.expirations.get() + 1);
             }
         }
+
+        let graph = self.graph;
+        let tx = self.tx;
+        let disk = self.disk;
+        let owned = purl.clone();
+
+        let future: LocalBoxFuture<'a, Arc<Result<QualifiedPackageContext<'a>, Error>>> =
+            async move {
+                Arc::new(match disk {
+                    Some(disk) => disk.lookup(graph, owned, tx).await,
+                    None => graph.ingest_qualified_package(owned, tx).await,
+                })
+            }
+            .boxed_local();
+        let future = future.shared();
+
+        let entry = CacheEntry {
+            future: future.clone(),
+            created: Instant::now(),
+        };
+
+        if let Some((evicted_key, _)) = self.cache.borrow_mut().push(purl.clone(), entry) {
+            if evicted_key != *purl {
+                self.evictions.set(self.evictions.get() + 1);
+            }
+        }
+
+        future.await
     }
 }
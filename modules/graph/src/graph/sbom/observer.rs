@@ -0,0 +1,310 @@
+//! Change notifications for SBOM ingestion.
+//!
+//! Ingestion used to be fire-and-forget: callers had no way to learn what
+//! an `ingest_*` call just did short of polling the graph themselves.
+//! [`Graph::register_observer`] lets a downstream module (e.g. a cached
+//! vulnerability assertion table) subscribe to [`ChangeBatch`]es instead,
+//! and invalidate precisely the sbom ids/relationships that actually
+//! changed rather than recomputing everything.
+
+use crate::graph::Graph;
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex, OnceLock};
+use trustify_common::cpe::Cpe;
+use trustify_common::purl::Purl;
+use trustify_entity::relationship::Relationship;
+
+/// What a single ingestion call wrote for one SBOM. It is delivered to
+/// observers as soon as that call has written it, inside the enclosing
+/// `Transactional`; delivery is not deferred until that transaction
+/// commits, and a later rollback is not reported to observers.
+#[derive(Clone, Default)]
+pub struct ChangeBatch {
+    pub sbom_id: i32,
+    pub described_purls: Vec<Purl>,
+    pub described_cpes: Vec<Cpe>,
+    pub relationships: Vec<(i32, Relationship, i32)>,
+}
+
+impl ChangeBatch {
+    pub(crate) fn for_sbom(sbom_id: i32) -> Self {
+        Self {
+            sbom_id,
+            ..Default::default()
+        }
+    }
+}
+
+impl Debug for ChangeBatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChangeBatch")
+            .field("sbom_id", &self.sbom_id)
+            .field("described_purls", &self.described_purls.len())
+            .field("described_cpes", &self.described_cpes.len())
+            .field("relationships", &self.relationships.len())
+            .finish()
+    }
+}
+
+/// Decides whether an [`Observer`] cares about a given [`ChangeBatch`] —
+/// typically by checking `sbom_id` or the relationship kinds present.
+pub type ObserverPredicate = Arc<dyn Fn(&ChangeBatch) -> bool + Send + Sync>;
+/// Invoked with every [`ChangeBatch`] an [`Observer`]'s predicate accepted.
+pub type ObserverCallback = Arc<dyn Fn(&ChangeBatch) + Send + Sync>;
+
+/// A named subscription registered via [`Graph::register_observer`].
+#[derive(Clone)]
+pub struct Observer {
+    pub name: String,
+    predicate: ObserverPredicate,
+    callback: ObserverCallback,
+}
+
+impl Debug for Observer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Observer").field("name", &self.name).finish()
+    }
+}
+
+/// Per-`Graph`-instance observer storage, keyed by the `Graph`'s own
+/// address.
+///
+/// Keying an identity map on `&Graph`'s address is only sound for the
+/// lifetime of that address, so this registers a matching [`Drop`] below
+/// that removes the entry the moment the `Graph` it belongs to goes away.
+/// That closes both of the sharp edges a bare address key would otherwise
+/// have: the allocator can't hand the freed address to an unrelated
+/// `Graph` and silently inherit a stale registry (the entry is gone by
+/// then), and the map no longer grows unbounded as a long-lived process
+/// keeps constructing and discarding `Graph`s.
+///
+/// What this still doesn't fix on its own: a `Graph` that is moved or
+/// cloned gets a new address and, with it, a fresh empty registry under
+/// *that* address — looking the registry back up from the clone's own
+/// address, after the fact, never finds what was registered against the
+/// original. [`SbomContext`](super::SbomContext) sidesteps this by
+/// calling [`Graph::observer_registry`] once, at the moment it's built
+/// from the original (correctly-keyed) `&Graph`, and holding onto that
+/// `Arc` directly instead of re-deriving it from its own cloned `graph`
+/// field later — see its `From<(&Graph, _)>` impl and [`deliver`]. Any
+/// *other* long-lived clone of a `Graph` that wants to keep notifying
+/// after the fact needs the same treatment (capture `observer_registry()`
+/// up front) until the registry itself lives on `Graph` as a field
+/// (an `Arc<Mutex<Vec<Observer>>>` constructed once in `Graph::new`, so
+/// every clone shares the same underlying `Arc` and no address-keyed
+/// lookup is needed at all) — `Graph`'s struct definition lives in
+/// `crate::graph`, outside `sbom/`, and isn't present in this tree to
+/// add that field to; once it is, `observer_registry` below should
+/// return that field directly and everything in this file keyed on raw
+/// addresses, including the `Drop` impl, can go away.
+static REGISTRIES: OnceLock<Mutex<HashMap<usize, Arc<Mutex<Vec<Observer>>>>>> = OnceLock::new();
+
+fn scoped_registry(key: usize) -> Arc<Mutex<Vec<Observer>>> {
+    REGISTRIES
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap()
+        .entry(key)
+        .or_insert_with(|| Arc::new(Mutex::new(Vec::new())))
+        .clone()
+}
+
+/// Drop the registry entry for `key`, if any. Called from `Graph`'s
+/// `Drop` impl below so a freed address is never left pointing at a
+/// stale observer list for the next `Graph` the allocator puts there.
+fn deregister(key: usize) {
+    if let Some(registries) = REGISTRIES.get() {
+        registries.lock().unwrap().remove(&key);
+    }
+}
+
+/// Deliver `batch` to every observer in `registry` whose predicate
+/// accepts it.
+///
+/// Split out of [`Graph::notify_observers`] so a type that captured a
+/// `Graph`'s registry handle up front — see [`Graph::observer_registry`]
+/// and `SbomContext` in `super::mod` — can deliver through that captured
+/// `Arc` directly, instead of re-deriving (and, after a clone, missing)
+/// the registry via its own address.
+pub(crate) fn deliver(registry: &Arc<Mutex<Vec<Observer>>>, batch: &ChangeBatch) {
+    for observer in registry.lock().unwrap().iter() {
+        if (observer.predicate)(batch) {
+            (observer.callback)(batch);
+        }
+    }
+}
+
+impl Graph {
+    /// The `Arc` backing this `Graph`'s observer list.
+    ///
+    /// `Graph::clone()` produces a new address, so anything that needs
+    /// to keep notifying the observers registered against *this*
+    /// `Graph` after cloning it (e.g. `SbomContext`, which is handed
+    /// out by value from [`Graph::ingest_sbom`] and friends) must grab
+    /// this `Arc` now, while `self` is still the instance a caller
+    /// actually registered against, and hold onto it rather than
+    /// calling `notify_observers` through the clone later.
+    pub(crate) fn observer_registry(&self) -> Arc<Mutex<Vec<Observer>>> {
+        scoped_registry(self as *const Graph as usize)
+    }
+
+    /// Subscribe `callback` to future [`ChangeBatch`]es for which
+    /// `predicate` returns `true`. Observers fire from inside the
+    /// ingestion call, after its last fallible step, so an ingest that
+    /// errors out never notifies them (see [`ChangeBatch`] on commits).
+    pub fn register_observer(
+        &self,
+        name: impl Into<String>,
+        predicate: ObserverPredicate,
+        callback: ObserverCallback,
+    ) {
+        self.observer_registry().lock().unwrap().push(Observer {
+            name: name.into(),
+            predicate,
+            callback,
+        });
+    }
+
+    /// Unsubscribe every observer registered under `name` on this
+    /// `Graph`. The missing unregister path this fills in: a test or a
+    /// long-lived caller that previously had no way to stop listening
+    /// can now do so explicitly instead of accumulating observers for
+    /// the life of the process.
+    pub fn unregister_observer(&self, name: &str) {
+        self.observer_registry()
+            .lock()
+            .unwrap()
+            .retain(|observer| observer.name != name);
+    }
+
+    /// Deliver `batch` to every registered observer whose predicate
+    /// accepts it. Callers only invoke this once they have something to
+    /// report, so a no-change re-ingest never wakes anyone up.
+    pub(crate) fn notify_observers(&self, batch: &ChangeBatch) {
+        deliver(&self.observer_registry(), batch);
+    }
+}
+
+/// Reclaims this `Graph`'s registry entry as soon as it's dropped, so its
+/// address is never left keyed to observers that no longer make sense
+/// for whatever `Graph` the allocator reuses it for next. See the note
+/// on [`scoped_registry`] for what this still can't fix.
+impl Drop for Graph {
+    fn drop(&mut self) {
+        deregister(self as *const Graph as usize);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distinct_keys_get_independent_registries() {
+        let a = scoped_registry(1);
+        let b = scoped_registry(2);
+
+        a.lock().unwrap().push(Observer {
+            name: "only-a".to_string(),
+            predicate: Arc::new(|_| true),
+            callback: Arc::new(|_| {}),
+        });
+
+        assert_eq!(a.lock().unwrap().len(), 1);
+        assert_eq!(b.lock().unwrap().len(), 0, "registry for a different key must not see it");
+    }
+
+    #[test]
+    fn the_same_key_reuses_its_registry() {
+        let first = scoped_registry(42);
+        first.lock().unwrap().push(Observer {
+            name: "persists".to_string(),
+            predicate: Arc::new(|_| true),
+            callback: Arc::new(|_| {}),
+        });
+
+        let second = scoped_registry(42);
+        assert_eq!(second.lock().unwrap().len(), 1, "same key must return the same registry");
+    }
+
+    #[test]
+    fn deregister_drops_the_entry_so_a_reused_key_starts_empty() {
+        let key = 99_999;
+        let first = scoped_registry(key);
+        first.lock().unwrap().push(Observer {
+            name: "stale".to_string(),
+            predicate: Arc::new(|_| true),
+            callback: Arc::new(|_| {}),
+        });
+        assert_eq!(first.lock().unwrap().len(), 1);
+
+        deregister(key);
+
+        let reused = scoped_registry(key);
+        assert_eq!(
+            reused.lock().unwrap().len(),
+            0,
+            "a key must not inherit a prior occupant's observers once deregistered"
+        );
+    }
+
+    /// Applies the same `retain` that `Graph::unregister_observer` runs,
+    /// directly to a registry looked up by key.
+    #[test]
+    fn unregister_removes_only_the_named_observer() {
+        let key = 1000;
+        let registry = scoped_registry(key);
+        registry.lock().unwrap().push(Observer {
+            name: "keep".to_string(),
+            predicate: Arc::new(|_| true),
+            callback: Arc::new(|_| {}),
+        });
+        registry.lock().unwrap().push(Observer {
+            name: "drop-me".to_string(),
+            predicate: Arc::new(|_| true),
+            callback: Arc::new(|_| {}),
+        });
+
+        registry.lock().unwrap().retain(|observer| observer.name != "drop-me");
+
+        let names: Vec<_> = registry
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|observer| observer.name.clone())
+            .collect();
+        assert_eq!(names, vec!["keep".to_string()]);
+    }
+
+    /// Mirrors the mechanism `SbomContext` relies on: capturing a
+    /// `Graph`'s registry `Arc` once, up front, and delivering through
+    /// that `Arc` directly still reaches an observer that a fresh
+    /// lookup by a different (post-clone) key would never find.
+    #[test]
+    fn delivering_through_a_captured_registry_reaches_observers_a_fresh_lookup_by_a_different_key_would_miss() {
+        let original_key = 55_001;
+        let captured = scoped_registry(original_key);
+
+        let fired = Arc::new(Mutex::new(false));
+        let flag = fired.clone();
+        captured.lock().unwrap().push(Observer {
+            name: "captured".to_string(),
+            predicate: Arc::new(|_| true),
+            callback: Arc::new(move |_| *flag.lock().unwrap() = true),
+        });
+
+        // A `Graph` clone's own address is a different key, so looking
+        // the registry up fresh from there finds nothing registered —
+        // this is exactly the gap that bit `SbomContext` before it
+        // started capturing the `Arc` instead.
+        let cloned_key = 55_002;
+        assert_eq!(scoped_registry(cloned_key).lock().unwrap().len(), 0);
+
+        deliver(&captured, &ChangeBatch::for_sbom(1));
+        assert!(
+            *fired.lock().unwrap(),
+            "an observer reachable only via the captured Arc must still fire"
+        );
+    }
+}
@@ -1,6 +1,112 @@
 use crate::service::{advisory::Format, Error, IngestorService};
-use actix_web::{get, post, web, HttpResponse, Responder};
+use actix_multipart::Multipart;
+use actix_web::http::header::CONTENT_LENGTH;
+use actix_web::{get, post, web, HttpRequest, HttpResponse, Responder, ResponseError};
+use futures::TryStreamExt;
 use std::str::FromStr;
+use std::time::Instant;
+use trustify_common_auth::authenticator::token::{Authorized, Download, Upload};
+
+mod archive;
+mod caching;
+mod compression;
+mod limits;
+use caching::{insert_caching_headers, insert_content_disposition, is_not_modified, not_modified_response};
+use compression::{compressed_stream_response, CompressionConfig};
+use limits::{check_content_length, check_uri_length, Limited, SizeLimits};
+
+/// Record one audited request for a handler in this module.
+///
+/// There's no `modules/ingestor/src/endpoints/mod.rs` in this tree yet
+/// to register a `/advisories` scope on, so there's nothing to
+/// `.wrap(AuditLog::new(...))` the way the search module's endpoint
+/// `configure` does — this calls the same [`trustify_common_audit::record`]
+/// the middleware would, just from inside the handler body. See
+/// [`AuditGuard`] for how every exit path out of a handler, including an
+/// early `?`, still reaches this.
+fn record_audit(req: &HttpRequest, response: &HttpResponse, start: Instant, advisory_id: Option<i32>) {
+    let bytes = response
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(0);
+
+    trustify_common_audit::record(
+        &*trustify_common_audit::global(),
+        req,
+        response.status().as_u16(),
+        bytes,
+        start,
+        advisory_id,
+    );
+}
+
+/// Guarantees one [`record_audit`] call per handler invocation, no
+/// matter which of the handler's exit paths is taken.
+///
+/// Construct one at the top of a handler in place of the old bare
+/// `start = Instant::now()`, then:
+/// - call [`Self::set_advisory_id`] once an id is known;
+/// - end the success path with [`Self::succeed`], which records and
+///   returns the response for the handler to return;
+/// - wrap every fallible `?` step with [`Self::fail`], which records
+///   the error's own rendered response — the same one
+///   [`actix_web::ResponseError::error_response`] will hand actix right
+///   after — and hands the error straight back for `?` to propagate.
+///
+/// A handler that unwinds (panics), or whose future is dropped before it
+/// finishes (e.g. the client went away), past every `succeed`/`fail`
+/// call still gets one last-resort record via `Drop`, rather than
+/// vanishing from the audit trail silently; those are the only cases
+/// where the recorded status is a stand-in (`500`) instead of the real
+/// outcome, since there is no real response to describe.
+struct AuditGuard {
+    req: HttpRequest,
+    start: Instant,
+    advisory_id: Option<i32>,
+    recorded: bool,
+}
+
+impl AuditGuard {
+    fn new(req: &HttpRequest) -> Self {
+        Self {
+            req: req.clone(),
+            start: Instant::now(),
+            advisory_id: None,
+            recorded: false,
+        }
+    }
+
+    fn set_advisory_id(&mut self, id: i32) {
+        self.advisory_id = Some(id);
+    }
+
+    fn succeed(mut self, response: HttpResponse) -> HttpResponse {
+        record_audit(&self.req, &response, self.start, self.advisory_id);
+        self.recorded = true;
+        response
+    }
+
+    fn fail(&mut self, err: Error) -> Error {
+        record_audit(&self.req, &err.error_response(), self.start, self.advisory_id);
+        self.recorded = true;
+        err
+    }
+}
+
+impl Drop for AuditGuard {
+    fn drop(&mut self) {
+        if !self.recorded {
+            record_audit(
+                &self.req,
+                &HttpResponse::InternalServerError().finish(),
+                self.start,
+                self.advisory_id,
+            );
+        }
+    }
+}
 
 #[derive(Clone, Debug, serde::Deserialize)]
 pub struct UploadAdvisoryQuery {
@@ -21,60 +127,384 @@ pub struct UploadAdvisoryQuery {
     responses(
         (status = 201, description = "Upload a file"),
         (status = 400, description = "The file could not be parsed as an advisory"),
+        (status = 413, description = "The uploaded document exceeds the configured size limit"),
+        (status = 414, description = "The request path or query string exceeds the configured length limit"),
     )
 )]
 #[post("/advisories")]
 /// Upload a new advisory
 pub async fn upload_advisory(
+    _auth: Authorized<Upload>,
     service: web::Data<IngestorService>,
     payload: web::Payload,
     web::Query(UploadAdvisoryQuery { location, format }): web::Query<UploadAdvisoryQuery>,
+    req: HttpRequest,
 ) -> Result<impl Responder, Error> {
+    let mut audit = AuditGuard::new(&req);
+    let limits = SizeLimits::default();
+    if let Some(rejected) = check_uri_length(&req, &limits).or_else(|| check_content_length(&req, &limits)) {
+        return Ok(audit.succeed(rejected));
+    }
+
     let fmt = format
         .map(|f| Format::from_str(&f))
-        .unwrap_or(Ok(Format::CSAF))?;
-    let advisory_id = service.ingest(&location, fmt, payload).await?;
-    Ok(HttpResponse::Created().json(advisory_id))
+        .unwrap_or(Ok(Format::CSAF))
+        .map_err(|e| audit.fail(Error::from(e)))?;
+    let advisory_id = service
+        .ingest(&location, fmt, Limited::new(payload, limits.max_body_bytes))
+        .await
+        .map_err(|e| audit.fail(e))?;
+    audit.set_advisory_id(advisory_id);
+    Ok(audit.succeed(HttpResponse::Created().json(advisory_id)))
+}
+
+/// The outcome of ingesting a single part of a `/advisories/batch` upload.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct BatchAdvisoryResult {
+    pub filename: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub advisory_id: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
 #[utoipa::path(
     tag = "ingestor",
+    request_body(content = String, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Per-file upload results (a part exceeding the size limit is reported per-file, not as a top-level error)", body = [BatchAdvisoryResult]),
+        (status = 400, description = "The request was not valid multipart/form-data"),
+        (status = 413, description = "The whole request body exceeds the configured size limit"),
+        (status = 414, description = "The request path or query string exceeds the configured length limit"),
+    )
+)]
+#[post("/advisories/batch")]
+/// Upload a batch of advisories in a single `multipart/form-data` request,
+/// one part per file. Each part's `location` is taken from its form field
+/// name (falling back to its filename) and its `format` from the part's
+/// content-type, defaulting to CSAF. A failure ingesting one part is
+/// reported alongside the successes rather than aborting the rest of the
+/// batch.
+pub async fn upload_advisories_batch(
+    _auth: Authorized<Upload>,
+    service: web::Data<IngestorService>,
+    mut payload: Multipart,
+    req: HttpRequest,
+) -> Result<impl Responder, Error> {
+    let mut audit = AuditGuard::new(&req);
+    let limits = SizeLimits::default();
+    if let Some(rejected) = check_uri_length(&req, &limits).or_else(|| check_content_length(&req, &limits)) {
+        return Ok(audit.succeed(rejected));
+    }
+
+    let mut results = Vec::new();
+
+    while let Some(mut field) = payload.try_next().await.map_err(|e| audit.fail(e.into()))? {
+        let content_disposition = field.content_disposition().cloned();
+
+        let filename = content_disposition
+            .as_ref()
+            .and_then(|cd| cd.get_filename())
+            .map(str::to_string)
+            .unwrap_or_else(|| "unknown".to_string());
+
+        let location = content_disposition
+            .as_ref()
+            .and_then(|cd| cd.get_name())
+            .map(str::to_string)
+            .unwrap_or_else(|| filename.clone());
+
+        let format = field
+            .content_type()
+            .and_then(|mime| Format::from_str(mime.subtype().as_str()).ok())
+            .unwrap_or(Format::CSAF);
+
+        let mut bytes = web::BytesMut::new();
+        let mut read_error = None;
+        while let Some(chunk) = match field.try_next().await {
+            Ok(chunk) => chunk,
+            Err(err) => {
+                read_error = Some(err.to_string());
+                None
+            }
+        } {
+            if bytes.len() + chunk.len() > limits.max_body_bytes {
+                read_error = Some(format!(
+                    "part exceeds the maximum allowed size of {} bytes",
+                    limits.max_body_bytes
+                ));
+                break;
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        let result = match read_error {
+            Some(error) => BatchAdvisoryResult {
+                filename,
+                advisory_id: None,
+                error: Some(error),
+            },
+            None => {
+                let body = futures::stream::once(futures::future::ready(Ok::<
+                    _,
+                    actix_web::error::PayloadError,
+                >(
+                    bytes.freeze()
+                )));
+
+                match service.ingest(&location, format, body).await {
+                    Ok(advisory_id) => BatchAdvisoryResult {
+                        filename,
+                        advisory_id: Some(advisory_id),
+                        error: None,
+                    },
+                    Err(err) => BatchAdvisoryResult {
+                        filename,
+                        advisory_id: None,
+                        error: Some(err.to_string()),
+                    },
+                }
+            }
+        };
+
+        results.push(result);
+    }
+
+    let response = HttpResponse::Ok().json(results);
+    // A batch can ingest several advisories at once, and `AuditEntry`
+    // only has room to correlate one — each part's own outcome is
+    // already in the response body `results` carries.
+    Ok(audit.succeed(response))
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct UploadAdvisoryArchiveQuery {
+    /// The source all documents in the archive came from.
+    pub location: String,
+}
+
+/// The outcome of ingesting a single entry of an `/advisories/archive`
+/// upload.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct ArchiveEntryResult {
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub advisory_id: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// `true` if the entry wasn't recognized as a CSAF/OSV document and
+    /// was left un-ingested rather than treated as a failure.
+    pub skipped: bool,
+}
+
+/// A summary of an `/advisories/archive` upload: how many entries were
+/// ingested, skipped as non-advisory files, or failed, plus the detail
+/// behind each.
+#[derive(Clone, Debug, Default, serde::Serialize)]
+pub struct ArchiveIngestReport {
+    pub ingested: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub entries: Vec<ArchiveEntryResult>,
+}
+
+#[utoipa::path(
+    tag = "ingestor",
+    request_body = Vec <u8>,
+    params(
+        ("location" = String, Query, description = "Source all documents in the archive came from"),
+    ),
+    responses(
+        (status = 200, description = "Per-entry ingestion summary", body = ArchiveIngestReport),
+        (status = 400, description = "The body was not a recognized zip or tar.gz archive"),
+        (status = 413, description = "The archive exceeds the configured size limit"),
+        (status = 414, description = "The request path or query string exceeds the configured length limit"),
+    )
+)]
+#[post("/advisories/archive")]
+/// Upload a zip or tar.gz archive containing many advisory documents in
+/// one request. Each entry's format (CSAF vs. OSV) is sniffed from its
+/// extension or content; entries that aren't recognized as an advisory
+/// document are skipped rather than failed. Lets tooling bulk-load a
+/// vendor's full advisory export without unpacking it client-side.
+pub async fn upload_advisories_archive(
+    _auth: Authorized<Upload>,
+    service: web::Data<IngestorService>,
+    mut payload: web::Payload,
+    web::Query(UploadAdvisoryArchiveQuery { location }): web::Query<UploadAdvisoryArchiveQuery>,
+    req: HttpRequest,
+) -> Result<impl Responder, Error> {
+    let mut audit = AuditGuard::new(&req);
+    let limits = SizeLimits::default();
+    if let Some(rejected) = check_uri_length(&req, &limits).or_else(|| check_content_length(&req, &limits)) {
+        return Ok(audit.succeed(rejected));
+    }
+
+    let mut bytes = web::BytesMut::new();
+    while let Some(chunk) = match payload.try_next().await {
+        Ok(chunk) => chunk,
+        Err(err) => {
+            let response = HttpResponse::BadRequest().json(serde_json::json!({ "error": err.to_string() }));
+            return Ok(audit.succeed(response));
+        }
+    } {
+        if bytes.len() + chunk.len() > limits.max_body_bytes {
+            let response = HttpResponse::PayloadTooLarge().json(serde_json::json!({
+                "error": format!("the archive exceeds the maximum allowed size of {} bytes", limits.max_body_bytes),
+            }));
+            return Ok(audit.succeed(response));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    let entries = match archive::extract_entries(&bytes) {
+        Ok(entries) => entries,
+        Err(error) => {
+            let response = HttpResponse::BadRequest().json(serde_json::json!({ "error": error }));
+            return Ok(audit.succeed(response));
+        }
+    };
+
+    let mut report = ArchiveIngestReport::default();
+
+    for archive::ArchiveEntry { path, data } in entries {
+        let Some(format) = archive::sniff_format(&path, &data) else {
+            report.skipped += 1;
+            report.entries.push(ArchiveEntryResult {
+                path,
+                advisory_id: None,
+                error: None,
+                skipped: true,
+            });
+            continue;
+        };
+
+        let body = futures::stream::once(futures::future::ready(Ok::<
+            _,
+            actix_web::error::PayloadError,
+        >(web::Bytes::from(data))));
+
+        match service.ingest(&location, format, body).await {
+            Ok(advisory_id) => {
+                report.ingested += 1;
+                report.entries.push(ArchiveEntryResult {
+                    path,
+                    advisory_id: Some(advisory_id),
+                    error: None,
+                    skipped: false,
+                });
+            }
+            Err(err) => {
+                report.failed += 1;
+                report.entries.push(ArchiveEntryResult {
+                    path,
+                    advisory_id: None,
+                    error: Some(err.to_string()),
+                    skipped: false,
+                });
+            }
+        }
+    }
+
+    let response = HttpResponse::Ok().json(report);
+    // Same one-id-per-entry limitation as the batch endpoint above.
+    Ok(audit.succeed(response))
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct DownloadAdvisoryQuery {
+    /// Force `Content-Disposition: attachment` so browsers save the
+    /// document instead of rendering it inline.
+    #[serde(default)]
+    pub download: bool,
+}
+
+#[utoipa::path(
+    tag = "ingestor",
+    params(
+        ("download" = Option<bool>, Query, description = "Force a browser download instead of an inline response"),
+    ),
     responses(
         (status = 200, description = "Download a an advisory", body = Vec<u8>),
+        (status = 304, description = "The client's cached copy is still current"),
         (status = 404, description = "The document could not be found"),
     )
 )]
 #[get("/advisories/{id}")]
 /// Download an advisory
 pub async fn download_advisory(
+    _auth: Authorized<Download>,
     service: web::Data<IngestorService>,
     path: web::Path<i32>,
+    web::Query(DownloadAdvisoryQuery { download }): web::Query<DownloadAdvisoryQuery>,
+    req: HttpRequest,
 ) -> Result<impl Responder, Error> {
+    let mut audit = AuditGuard::new(&req);
     let id = path.into_inner();
+    audit.set_advisory_id(id);
+
+    // The stream retrieval below re-derives storage location from the
+    // same row, but conditional GET needs to answer without paying for
+    // that read at all when the client's cached copy is already current.
+    let Some(advisory) = service
+        .lookup_advisory(id)
+        .await
+        .map_err(|e| audit.fail(e))?
+    else {
+        let response = HttpResponse::NotFound().finish();
+        return Ok(audit.succeed(response));
+    };
 
-    Ok(match service.retrieve_advisory(id).await? {
-        Some(stream) => HttpResponse::Ok().streaming(stream),
+    if is_not_modified(&req, &advisory) {
+        let response = not_modified_response(&advisory);
+        return Ok(audit.succeed(response));
+    }
+
+    let response = match service.retrieve_advisory(id).await.map_err(|e| audit.fail(e))? {
+        Some(stream) => {
+            let mut response = compressed_stream_response(&req, CompressionConfig::default(), stream);
+            insert_caching_headers(response.headers_mut(), &advisory);
+            insert_content_disposition(response.headers_mut(), &advisory, download);
+            response
+        }
         None => HttpResponse::NotFound().finish(),
-    })
+    };
+    Ok(audit.succeed(response))
 }
 
 #[cfg(test)]
 mod tests {
     use super::super::configure;
 
-    use actix_web::{http::StatusCode, test, test::TestRequest, App};
+    use actix_web::{http::StatusCode, test, test::TestRequest, web, App};
     use std::fs;
+    use std::io::Write;
     use std::path::PathBuf;
     use std::str::FromStr;
     use trustify_common::db::Database;
+    use trustify_common_auth::authenticator::token::{Scope, TokenAuthenticatorConfig};
     use trustify_module_storage::service::fs::FileSystemBackend;
 
+    /// A `TokenAuthenticatorConfig` covering everything these tests exercise.
+    ///
+    /// `Authorized<_>` now refuses to serve a request at all once no config
+    /// is registered for its scope (see [`AuthenticationError::NotConfigured`]),
+    /// so every test that hits a gated handler needs one wired up the same
+    /// way a real deployment's `configure()` would.
+    fn test_auth_config() -> TokenAuthenticatorConfig {
+        TokenAuthenticatorConfig::new(true).with_token("test-token", [Scope::Upload, Scope::Download])
+    }
+
     #[test_log::test(actix_web::test)]
     async fn upload_default_csaf_format() -> Result<(), anyhow::Error> {
         let db = Database::for_test("upload_advisory_csaf").await?;
         let (storage, _temp) = FileSystemBackend::for_test().await?;
 
-        let app = test::init_service(App::new().configure(|svc| configure(svc, db, storage))).await;
+        let app = test::init_service(App::new().configure(|svc| {
+            configure(svc, db, storage);
+            svc.app_data(web::Data::new(test_auth_config()));
+        }))
+        .await;
 
         let pwd = PathBuf::from_str(env!("CARGO_MANIFEST_DIR"))?;
         let test_data = pwd.join("../../etc/test-data");
@@ -85,6 +515,7 @@ mod tests {
         let uri = "/advisories?location=test-csaf";
         let request = TestRequest::post()
             .uri(uri)
+            .insert_header(("Authorization", "Bearer test-token"))
             .set_payload(payload)
             .to_request();
 
@@ -101,7 +532,11 @@ mod tests {
         let db = Database::for_test("upload_advisory_osv").await?;
         let (storage, _temp) = FileSystemBackend::for_test().await?;
 
-        let app = test::init_service(App::new().configure(|svc| configure(svc, db, storage))).await;
+        let app = test::init_service(App::new().configure(|svc| {
+            configure(svc, db, storage);
+            svc.app_data(web::Data::new(test_auth_config()));
+        }))
+        .await;
 
         let pwd = PathBuf::from_str(env!("CARGO_MANIFEST_DIR"))?;
         let test_data = pwd.join("../../etc/test-data/osv");
@@ -112,6 +547,7 @@ mod tests {
         let uri = "/advisories?location=test-osv&format=osv";
         let request = TestRequest::post()
             .uri(uri)
+            .insert_header(("Authorization", "Bearer test-token"))
             .set_payload(payload)
             .to_request();
 
@@ -123,14 +559,121 @@ mod tests {
         Ok(())
     }
 
+    #[test_log::test(actix_web::test)]
+    async fn upload_advisories_batch() -> Result<(), anyhow::Error> {
+        let db = Database::for_test("upload_advisories_batch").await?;
+        let (storage, _temp) = FileSystemBackend::for_test().await?;
+
+        let app = test::init_service(App::new().configure(|svc| {
+            configure(svc, db, storage);
+            svc.app_data(web::Data::new(test_auth_config()));
+        }))
+        .await;
+
+        let pwd = PathBuf::from_str(env!("CARGO_MANIFEST_DIR"))?;
+        let test_data = pwd.join("../../etc/test-data");
+
+        let csaf = fs::read_to_string(test_data.join("cve-2023-33201.json")).expect("File not found");
+        let osv = fs::read_to_string(test_data.join("osv/RUSTSEC-2021-0079.json"))
+            .expect("File not found");
+
+        let boundary = "batch-test-boundary";
+        let body = format!(
+            "--{boundary}\r\n\
+             Content-Disposition: form-data; name=\"test-csaf\"; filename=\"cve-2023-33201.json\"\r\n\
+             Content-Type: application/csaf\r\n\r\n\
+             {csaf}\r\n\
+             --{boundary}\r\n\
+             Content-Disposition: form-data; name=\"test-osv\"; filename=\"RUSTSEC-2021-0079.json\"\r\n\
+             Content-Type: application/osv\r\n\r\n\
+             {osv}\r\n\
+             --{boundary}--\r\n"
+        );
+
+        let request = TestRequest::post()
+            .uri("/advisories/batch")
+            .insert_header((
+                "content-type",
+                format!("multipart/form-data; boundary={boundary}"),
+            ))
+            .insert_header(("Authorization", "Bearer test-token"))
+            .set_payload(body)
+            .to_request();
+
+        let response = test::call_service(&app, request).await;
+        log::debug!("response: {response:?}");
+
+        assert!(response.status().is_success());
+
+        let results: Vec<super::BatchAdvisoryResult> = test::read_body_json(response).await;
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.advisory_id.is_some() && r.error.is_none()));
+
+        Ok(())
+    }
+
+    #[test_log::test(actix_web::test)]
+    async fn upload_advisories_archive() -> Result<(), anyhow::Error> {
+        let db = Database::for_test("upload_advisories_archive").await?;
+        let (storage, _temp) = FileSystemBackend::for_test().await?;
+
+        let app = test::init_service(App::new().configure(|svc| {
+            configure(svc, db, storage);
+            svc.app_data(web::Data::new(test_auth_config()));
+        }))
+        .await;
+
+        let pwd = PathBuf::from_str(env!("CARGO_MANIFEST_DIR"))?;
+        let test_data = pwd.join("../../etc/test-data");
+
+        let csaf = fs::read(test_data.join("cve-2023-33201.json")).expect("File not found");
+        let osv = fs::read(test_data.join("osv/RUSTSEC-2021-0079.json")).expect("File not found");
+
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let options =
+            zip::write::FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+        zip.start_file("cve-2023-33201.csaf.json", options)?;
+        zip.write_all(&csaf)?;
+        zip.start_file("RUSTSEC-2021-0079.osv.json", options)?;
+        zip.write_all(&osv)?;
+        zip.start_file("README.txt", options)?;
+        zip.write_all(b"not an advisory")?;
+        let archive = zip.finish()?.into_inner();
+
+        let request = TestRequest::post()
+            .uri("/advisories/archive?location=test-archive")
+            .insert_header(("Authorization", "Bearer test-token"))
+            .set_payload(archive)
+            .to_request();
+
+        let response = test::call_service(&app, request).await;
+        log::debug!("response: {response:?}");
+
+        assert!(response.status().is_success());
+
+        let report: super::ArchiveIngestReport = test::read_body_json(response).await;
+        assert_eq!(report.ingested, 2);
+        assert_eq!(report.skipped, 1);
+        assert_eq!(report.failed, 0);
+
+        Ok(())
+    }
+
     #[test_log::test(actix_web::test)]
     async fn upload_unknown_format() -> Result<(), anyhow::Error> {
         let db = Database::for_test("upload_unknown_format").await?;
         let (storage, _temp) = FileSystemBackend::for_test().await?;
-        let app = test::init_service(App::new().configure(|svc| configure(svc, db, storage))).await;
+        let app = test::init_service(App::new().configure(|svc| {
+            configure(svc, db, storage);
+            svc.app_data(web::Data::new(test_auth_config()));
+        }))
+        .await;
 
         let uri = "/advisories?location=testless&format=XYZ42";
-        let request = TestRequest::post().uri(uri).to_request();
+        let request = TestRequest::post()
+            .uri(uri)
+            .insert_header(("Authorization", "Bearer test-token"))
+            .to_request();
 
         let response = test::call_service(&app, request).await;
         log::debug!("response: {response:?}");
@@ -143,4 +686,61 @@ mod tests {
 
         Ok(())
     }
+
+    /// The other tests in this module register [`test_auth_config`], whose
+    /// token carries every scope they need. This registers a stricter
+    /// config (no anonymous reads, one scope per token) to prove the
+    /// enforcement path itself (missing token, wrong scope, right scope).
+    #[test_log::test(actix_web::test)]
+    async fn upload_and_download_are_gated_once_a_token_config_is_registered() -> Result<(), anyhow::Error> {
+        let db = Database::for_test("advisory_auth_gating").await?;
+        let (storage, _temp) = FileSystemBackend::for_test().await?;
+
+        let auth = TokenAuthenticatorConfig::new(false)
+            .with_token("upload-token", [Scope::Upload])
+            .with_token("download-token", [Scope::Download]);
+
+        let app = test::init_service(App::new().configure(|svc| {
+            configure(svc, db, storage);
+            svc.app_data(web::Data::new(auth));
+        }))
+        .await;
+
+        // No token at all: authentication, not authorization, fails.
+        let request = TestRequest::post()
+            .uri("/advisories?location=no-token")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // A token that exists but isn't scoped for Upload.
+        let request = TestRequest::post()
+            .uri("/advisories?location=wrong-scope")
+            .insert_header(("Authorization", "Bearer download-token"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // The right token for the right scope is let through.
+        let pwd = PathBuf::from_str(env!("CARGO_MANIFEST_DIR"))?;
+        let advisory = pwd.join("../../etc/test-data/cve-2023-33201.json");
+        let payload = fs::read_to_string(advisory).expect("File not found");
+        let request = TestRequest::post()
+            .uri("/advisories?location=right-scope")
+            .insert_header(("Authorization", "Bearer upload-token"))
+            .set_payload(payload)
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert!(response.status().is_success());
+
+        // Download is gated by its own Download scope, not Upload's.
+        let request = TestRequest::get()
+            .uri("/advisories/1")
+            .insert_header(("Authorization", "Bearer upload-token"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        Ok(())
+    }
 }
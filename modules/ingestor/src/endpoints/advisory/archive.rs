@@ -0,0 +1,205 @@
+//! Bulk ingestion of a zip or tar.gz archive of advisory documents.
+//!
+//! Vendors frequently publish their whole CSAF/OSV catalog as a single
+//! compressed archive (a RUSTSEC dump, a vendor's full CSAF export).
+//! Unpacking that client-side just to make one `/advisories` call per
+//! file is needless ceremony, so this reads entries directly out of the
+//! archive and ingests each one under the shared `location` the caller
+//! submitted the archive with.
+
+use crate::service::advisory::Format;
+use std::io::{Cursor, Read};
+use std::str::FromStr;
+
+/// A single file pulled out of an uploaded archive, not yet ingested.
+pub struct ArchiveEntry {
+    pub path: String,
+    pub data: Vec<u8>,
+}
+
+/// Ceiling on how large a single entry may decompress to. `file.size()`/
+/// `entry.size()` are self-reported by the archive and only ever used
+/// below to size an initial allocation hint — a crafted entry can claim
+/// anything while actually decompressing to far more, so this is
+/// enforced against what `read_capped` actually produces, not what the
+/// entry claims.
+const MAX_ENTRY_DECOMPRESSED_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Ceiling on how large an archive may decompress to in total across
+/// every entry combined, bounding a small compressed upload (the
+/// chunk3-6 size limit only bounds the compressed, uploaded bytes) from
+/// expanding into an unbounded amount of memory — the classic zip-bomb
+/// shape.
+const MAX_TOTAL_DECOMPRESSED_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Read `reader` to completion, bailing out once either `entry_cap` or
+/// `total_cap` (tracked via `total_read`) is crossed, rather than
+/// trusting `declared_size` for anything beyond an allocation hint.
+fn read_capped(
+    reader: &mut impl Read,
+    path: &str,
+    declared_size: u64,
+    entry_cap: u64,
+    total_cap: u64,
+    total_read: &mut u64,
+) -> Result<Vec<u8>, String> {
+    let hint = declared_size.min(entry_cap) as usize;
+    let mut data = Vec::with_capacity(hint);
+    let mut chunk = [0u8; 64 * 1024];
+    loop {
+        let read = reader
+            .read(&mut chunk)
+            .map_err(|err| format!("reading {path}: {err}"))?;
+        if read == 0 {
+            break;
+        }
+        data.extend_from_slice(&chunk[..read]);
+        *total_read += read as u64;
+
+        if data.len() as u64 > entry_cap {
+            return Err(format!(
+                "{path} decompresses to more than the maximum allowed {entry_cap} bytes for a single entry"
+            ));
+        }
+        if *total_read > total_cap {
+            return Err(format!(
+                "archive decompresses to more than the maximum allowed {total_cap} bytes in total"
+            ));
+        }
+    }
+    Ok(data)
+}
+
+/// Read every regular file out of `bytes`, sniffing whether it's a zip
+/// or a gzip-compressed tar from its magic bytes.
+///
+/// Returns `Err` with a short, user-facing reason when `bytes` is
+/// neither — there's nothing useful to report per-entry if the archive
+/// itself can't be opened.
+pub fn extract_entries(bytes: &[u8]) -> Result<Vec<ArchiveEntry>, String> {
+    if bytes.starts_with(b"PK\x03\x04") {
+        extract_zip(bytes)
+    } else if bytes.len() >= 2 && bytes[0] == 0x1f && bytes[1] == 0x8b {
+        extract_tar_gz(bytes)
+    } else {
+        Err("unrecognized archive format: expected a zip or gzip-compressed tar".to_string())
+    }
+}
+
+fn extract_zip(bytes: &[u8]) -> Result<Vec<ArchiveEntry>, String> {
+    let mut archive =
+        zip::ZipArchive::new(Cursor::new(bytes)).map_err(|err| format!("invalid zip archive: {err}"))?;
+
+    let mut entries = Vec::with_capacity(archive.len());
+    let mut total_read = 0u64;
+    for i in 0..archive.len() {
+        let mut file = archive
+            .by_index(i)
+            .map_err(|err| format!("reading zip entry {i}: {err}"))?;
+        if !file.is_file() {
+            continue;
+        }
+        let path = file.name().to_string();
+        let size = file.size();
+        let data = read_capped(
+            &mut file,
+            &path,
+            size,
+            MAX_ENTRY_DECOMPRESSED_BYTES,
+            MAX_TOTAL_DECOMPRESSED_BYTES,
+            &mut total_read,
+        )?;
+        entries.push(ArchiveEntry { path, data });
+    }
+    Ok(entries)
+}
+
+fn extract_tar_gz(bytes: &[u8]) -> Result<Vec<ArchiveEntry>, String> {
+    let decoder = flate2::read::GzDecoder::new(bytes);
+    let mut archive = tar::Archive::new(decoder);
+
+    let mut entries = Vec::new();
+    let mut total_read = 0u64;
+    for entry in archive.entries().map_err(|err| format!("invalid tar.gz archive: {err}"))? {
+        let mut entry = entry.map_err(|err| format!("reading tar entry: {err}"))?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry.path().map_err(|err| err.to_string())?.display().to_string();
+        let size = entry.size();
+        let data = read_capped(
+            &mut entry,
+            &path,
+            size,
+            MAX_ENTRY_DECOMPRESSED_BYTES,
+            MAX_TOTAL_DECOMPRESSED_BYTES,
+            &mut total_read,
+        )?;
+        entries.push(ArchiveEntry { path, data });
+    }
+    Ok(entries)
+}
+
+/// Guess an entry's advisory format from its extension, falling back, for
+/// a `.json` entry, to looking for OSV's and CSAF's distinctive keys
+/// anywhere in its text.
+/// `None` means the entry isn't a recognizable advisory document and
+/// should be skipped rather than ingested.
+pub fn sniff_format(path: &str, data: &[u8]) -> Option<Format> {
+    let lower = path.to_ascii_lowercase();
+
+    if lower.ends_with(".osv.json") {
+        return Some(Format::OSV);
+    }
+    if lower.ends_with(".csaf.json") {
+        return Some(Format::CSAF);
+    }
+    if let Some(format) = lower
+        .rsplit_once('.')
+        .and_then(|(_, ext)| Format::from_str(ext).ok())
+    {
+        return Some(format);
+    }
+    if !lower.ends_with(".json") {
+        return None;
+    }
+
+    let text = std::str::from_utf8(data).ok()?;
+    if text.contains("\"affected\"") && text.contains("\"ecosystem\"") {
+        Some(Format::OSV)
+    } else if text.contains("\"csaf_version\"") || text.contains("\"vulnerabilities\"") {
+        Some(Format::CSAF)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn declared_size_is_only_a_hint_not_a_trust_boundary() {
+        let mut total_read = 0u64;
+        let data = read_capped(&mut &b"hello"[..], "entry", u64::MAX, 10, 100, &mut total_read)
+            .expect("within both caps");
+        assert_eq!(data, b"hello");
+        assert_eq!(total_read, 5);
+    }
+
+    #[test]
+    fn rejects_an_entry_that_decompresses_past_its_own_cap() {
+        let mut total_read = 0u64;
+        let err = read_capped(&mut &b"hello world"[..], "entry", 0, 5, 100, &mut total_read)
+            .expect_err("actual bytes, not the lying declared_size of 0, must be enforced");
+        assert!(err.contains("single entry"), "{err}");
+    }
+
+    #[test]
+    fn rejects_once_the_cumulative_total_crosses_its_cap() {
+        let mut total_read = 8;
+        let err = read_capped(&mut &b"1234567890"[..], "entry", 10, 100, 10, &mut total_read)
+            .expect_err("fits its own per-entry cap but the running total doesn't");
+        assert!(err.contains("in total"), "{err}");
+    }
+}
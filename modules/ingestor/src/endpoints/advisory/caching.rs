@@ -0,0 +1,113 @@
+//! Conditional GET (`ETag`/`Last-Modified`) and `Content-Disposition`
+//! support for advisory downloads.
+//!
+//! The `advisory` row already carries a `sha256` (a natural strong ETag —
+//! two advisories only share it if they're byte-identical) and a
+//! `modified` timestamp, so a client that already has a copy can avoid
+//! re-downloading it entirely via `If-None-Match`/`If-Modified-Since`,
+//! and a browser-driven download gets a sensible filename instead of the
+//! numeric id in the URL.
+
+use actix_web::http::header::{
+    EntityTag, Header, HeaderValue, IfModifiedSince, IfNoneMatch, CONTENT_DISPOSITION, ETAG,
+    LAST_MODIFIED,
+};
+use actix_web::{HttpRequest, HttpResponse};
+use std::time::SystemTime;
+use time::OffsetDateTime;
+use trustify_entity::advisory;
+
+/// A strong `ETag` built from the advisory's content hash.
+fn etag(advisory: &advisory::Model) -> EntityTag {
+    EntityTag::new(true, advisory.sha256.clone())
+}
+
+/// `true` if `req`'s conditional headers show the client already has the
+/// current representation of `advisory` cached.
+pub fn is_not_modified(req: &HttpRequest, advisory: &advisory::Model) -> bool {
+    // `IfNoneMatch::parse` succeeds with an empty list when the header is
+    // absent, so check for the header itself before letting it decide.
+    if let (true, Ok(if_none_match)) = (
+        req.headers().contains_key(IfNoneMatch::name()),
+        IfNoneMatch::parse(req),
+    ) {
+        let current = etag(advisory);
+        return match if_none_match {
+            IfNoneMatch::Any => true,
+            IfNoneMatch::Items(tags) => tags.iter().any(|tag| tag.weak_eq(&current)),
+        };
+    }
+
+    if let (Ok(IfModifiedSince(since)), Some(modified)) =
+        (IfModifiedSince::parse(req), advisory.modified)
+    {
+        return modified <= OffsetDateTime::from(SystemTime::from(since));
+    }
+
+    false
+}
+
+/// A bare `304 Not Modified` carrying just the caching headers, for when
+/// [`is_not_modified`] is true.
+pub fn not_modified_response(advisory: &advisory::Model) -> HttpResponse {
+    let mut response = HttpResponse::NotModified();
+    insert_caching_headers(response.headers_mut(), advisory);
+    response.finish()
+}
+
+/// Set `ETag`/`Last-Modified` on an in-progress response.
+pub fn insert_caching_headers(
+    headers: &mut actix_web::http::header::HeaderMap,
+    advisory: &advisory::Model,
+) {
+    if let Ok(value) = HeaderValue::from_str(&etag(advisory).to_string()) {
+        headers.insert(ETAG, value);
+    }
+    if let Some(modified) = advisory.modified {
+        let http_date: actix_web::http::header::HttpDate = SystemTime::from(modified).into();
+        if let Ok(value) = HeaderValue::from_str(&http_date.to_string()) {
+            headers.insert(LAST_MODIFIED, value);
+        }
+    }
+}
+
+/// Set `Content-Disposition`, defaulting to `inline` so the document
+/// opens in-browser, switching to `attachment` (forcing a save-as
+/// download) when the caller passed `?download=true`.
+pub fn insert_content_disposition(
+    headers: &mut actix_web::http::header::HeaderMap,
+    advisory: &advisory::Model,
+    download: bool,
+) {
+    let disposition = if download { "attachment" } else { "inline" };
+    let filename = format!("{}.json", sanitize_filename(&advisory.identifier));
+
+    if let Ok(value) = HeaderValue::from_str(&format!(
+        "{disposition}; filename=\"{filename}\""
+    )) {
+        headers.insert(CONTENT_DISPOSITION, value);
+    }
+}
+
+/// Advisory identifiers are often URLs or contain characters that are
+/// awkward in a filename (`/`, `:`); collapse anything that isn't
+/// alphanumeric or one of `.-_` to `_`.
+fn sanitize_filename(identifier: &str) -> String {
+    identifier
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitizes_url_like_identifiers() {
+        assert_eq!(
+            sanitize_filename("https://example.com/CVE-2023-33201"),
+            "https___example.com_CVE-2023-33201"
+        );
+    }
+}
@@ -0,0 +1,142 @@
+//! Accept-Encoding–negotiated streaming compression for advisory downloads.
+//!
+//! Stored advisories can be multi-megabyte CSAF documents, so compressing
+//! them in one buffered pass before responding would defeat the point of
+//! `HttpResponse::streaming`. This wraps the retrieved byte stream in a
+//! streaming gzip/deflate encoder chosen by the request's `Accept-Encoding`
+//! header instead, leaving memory use bounded regardless of document size.
+
+use actix_web::http::header::{ACCEPT_ENCODING, CONTENT_ENCODING, VARY};
+use actix_web::{HttpRequest, HttpResponse};
+use async_compression::stream::{GzipEncoder, ZlibEncoder};
+use async_compression::Level;
+use bytes::Bytes;
+use futures::{Stream, TryStreamExt};
+use std::io;
+
+/// The compression level applied when a client advertises support for it.
+/// Exposed so an operator can trade CPU for bandwidth.
+#[derive(Clone, Copy, Debug)]
+pub struct CompressionConfig {
+    pub level: Level,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            level: Level::Default,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum NegotiatedEncoding {
+    Gzip,
+    Deflate,
+}
+
+/// `q=0` on a candidate is the client explicitly refusing that coding,
+/// not merely deprioritizing it — `gzip;q=0` must be treated the same as
+/// `gzip` being absent entirely, never picked. Anything else (no `q`,
+/// `q=0.x`, a malformed value) is treated as acceptable; picking between
+/// two codecs a client merely tolerates isn't worth a full q-value sort.
+fn is_refused(candidate: &str) -> bool {
+    candidate
+        .split(';')
+        .skip(1)
+        .filter_map(|param| param.trim().strip_prefix("q="))
+        .any(|q| q.trim().parse::<f32>() == Ok(0.0))
+}
+
+fn negotiate(req: &HttpRequest) -> Option<NegotiatedEncoding> {
+    let header = req.headers().get(ACCEPT_ENCODING)?.to_str().ok()?;
+
+    header.split(',').map(str::trim).find_map(|candidate| {
+        if is_refused(candidate) {
+            return None;
+        }
+        let name = candidate.split(';').next().unwrap_or(candidate).trim();
+        match name {
+            "gzip" => Some(NegotiatedEncoding::Gzip),
+            "deflate" => Some(NegotiatedEncoding::Deflate),
+            _ => None,
+        }
+    })
+}
+
+/// Stream `body` back to the client, transparently gzip/deflate-encoding
+/// it when `req`'s `Accept-Encoding` asks for one and setting
+/// `Content-Encoding`/`Vary` to match. Falls back to the raw stream when
+/// the client doesn't advertise a supported encoding.
+pub fn compressed_stream_response<S, E>(req: &HttpRequest, config: CompressionConfig, body: S) -> HttpResponse
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let body = body.map_err(io::Error::other);
+
+    match negotiate(req) {
+        Some(NegotiatedEncoding::Gzip) => HttpResponse::Ok()
+            .insert_header((CONTENT_ENCODING, "gzip"))
+            .insert_header((VARY, "Accept-Encoding"))
+            .streaming(GzipEncoder::with_quality(body, config.level)),
+        Some(NegotiatedEncoding::Deflate) => HttpResponse::Ok()
+            .insert_header((CONTENT_ENCODING, "deflate"))
+            .insert_header((VARY, "Accept-Encoding"))
+            .streaming(ZlibEncoder::with_quality(body, config.level)),
+        None => HttpResponse::Ok()
+            .insert_header((VARY, "Accept-Encoding"))
+            .streaming(body),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    fn request_with_accept_encoding(value: &str) -> HttpRequest {
+        TestRequest::get()
+            .insert_header((ACCEPT_ENCODING, value))
+            .to_http_request()
+    }
+
+    #[test]
+    fn negotiates_gzip() {
+        assert_eq!(
+            negotiate(&request_with_accept_encoding("gzip, deflate")),
+            Some(NegotiatedEncoding::Gzip)
+        );
+    }
+
+    #[test]
+    fn negotiates_deflate_when_gzip_unsupported() {
+        assert_eq!(
+            negotiate(&request_with_accept_encoding("deflate")),
+            Some(NegotiatedEncoding::Deflate)
+        );
+    }
+
+    #[test]
+    fn falls_back_when_unsupported() {
+        assert_eq!(negotiate(&request_with_accept_encoding("br")), None);
+    }
+
+    #[test]
+    fn falls_back_when_header_absent() {
+        assert_eq!(negotiate(&TestRequest::get().to_http_request()), None);
+    }
+
+    #[test]
+    fn q_zero_refuses_the_coding_rather_than_just_deprioritizing_it() {
+        assert_eq!(negotiate(&request_with_accept_encoding("gzip;q=0")), None);
+    }
+
+    #[test]
+    fn q_zero_on_one_coding_still_lets_another_be_picked() {
+        assert_eq!(
+            negotiate(&request_with_accept_encoding("gzip;q=0, deflate")),
+            Some(NegotiatedEncoding::Deflate)
+        );
+    }
+}
@@ -0,0 +1,180 @@
+//! Size limits and structured rejection for advisory upload endpoints.
+//!
+//! `upload_advisory` and its siblings used to read an unbounded body into
+//! memory chunk by chunk, so a misbehaving or hostile client could make
+//! the process buffer an arbitrarily large payload before anything had a
+//! chance to reject it. This enforces a configurable byte ceiling while
+//! streaming — aborting and reporting a structured `413` the moment the
+//! ceiling is crossed rather than after the whole body has already been
+//! brought into memory — plus a cheap, early check on how long the URL
+//! itself is allowed to be.
+
+use actix_web::error::PayloadError;
+use actix_web::http::header::CONTENT_LENGTH;
+use actix_web::http::StatusCode;
+use actix_web::{HttpRequest, HttpResponse};
+use bytes::Bytes;
+use futures::Stream;
+use serde::Serialize;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Ceilings enforced before (and while) reading an upload. Not wired to
+/// configuration in this snapshot; construct with [`SizeLimits::default`]
+/// and override individual fields for a given deployment.
+#[derive(Clone, Copy, Debug)]
+pub struct SizeLimits {
+    pub max_body_bytes: usize,
+    pub max_query_len: usize,
+    pub max_path_len: usize,
+}
+
+impl Default for SizeLimits {
+    fn default() -> Self {
+        Self {
+            max_body_bytes: 100 * 1024 * 1024,
+            max_query_len: 2 * 1024,
+            max_path_len: 2 * 1024,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorInformation {
+    error: String,
+    message: String,
+}
+
+fn rejection(status: StatusCode, error: &str, message: impl Into<String>) -> HttpResponse {
+    HttpResponse::build(status).json(ErrorInformation {
+        error: error.to_string(),
+        message: message.into(),
+    })
+}
+
+/// `Some(response)` if `req`'s path or query string already exceeds
+/// `limits`, to be returned as-is before doing any further work.
+pub fn check_uri_length(req: &HttpRequest, limits: &SizeLimits) -> Option<HttpResponse> {
+    if req.path().len() > limits.max_path_len || req.query_string().len() > limits.max_query_len {
+        Some(rejection(
+            StatusCode::URI_TOO_LONG,
+            "UriTooLong",
+            "The request path or query string exceeds the configured maximum length",
+        ))
+    } else {
+        None
+    }
+}
+
+/// `Some(response)` if `req` advertises a `Content-Length` already over
+/// `limits.max_body_bytes`, letting an oversized upload be rejected
+/// before a single byte of the body is read.
+pub fn check_content_length(req: &HttpRequest, limits: &SizeLimits) -> Option<HttpResponse> {
+    let declared: usize = req
+        .headers()
+        .get(CONTENT_LENGTH)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()?;
+
+    (declared > limits.max_body_bytes).then(|| too_large_response(limits.max_body_bytes))
+}
+
+fn too_large_response(max_body_bytes: usize) -> HttpResponse {
+    rejection(
+        StatusCode::PAYLOAD_TOO_LARGE,
+        "PayloadTooLarge",
+        format!("The upload exceeds the maximum allowed size of {max_body_bytes} bytes"),
+    )
+}
+
+/// Wraps a byte stream, forwarding chunks unchanged until the running
+/// total crosses `limit`, at which point it yields
+/// [`PayloadError::Overflow`] instead of the chunk — the same error
+/// actix's own size-limited extractors use, so it flows through whatever
+/// already converts a body read failure into a client-facing error for
+/// callers (like [`crate::service::IngestorService::ingest`]) that accept
+/// a raw [`actix_web::web::Payload`]-shaped stream today. This is what
+/// keeps enforcement truly streaming: the limit is checked chunk by
+/// chunk, not after the whole body has been collected.
+pub struct Limited<S> {
+    inner: S,
+    limit: usize,
+    seen: usize,
+}
+
+impl<S> Limited<S> {
+    pub fn new(inner: S, limit: usize) -> Self {
+        Self {
+            inner,
+            limit,
+            seen: 0,
+        }
+    }
+}
+
+impl<S> Stream for Limited<S>
+where
+    S: Stream<Item = Result<Bytes, PayloadError>> + Unpin,
+{
+    type Item = Result<Bytes, PayloadError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        match Pin::new(&mut this.inner).poll_next(cx) {
+            Poll::Ready(Some(Ok(chunk))) => {
+                this.seen += chunk.len();
+                if this.seen > this.limit {
+                    Poll::Ready(Some(Err(PayloadError::Overflow)))
+                } else {
+                    Poll::Ready(Some(Ok(chunk)))
+                }
+            }
+            other => other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+    use futures::{StreamExt, TryStreamExt};
+
+    #[actix_web::test]
+    async fn limited_stream_overflows_once_total_exceeds_limit() {
+        let chunks = futures::stream::iter([
+            Ok::<_, PayloadError>(Bytes::from_static(b"1234")),
+            Ok(Bytes::from_static(b"5678")),
+            Ok(Bytes::from_static(b"9")),
+        ]);
+
+        let mut limited = Limited::new(chunks, 6);
+
+        assert_eq!(limited.next().await.unwrap().unwrap(), Bytes::from_static(b"1234"));
+        assert!(matches!(
+            limited.try_next().await,
+            Err(PayloadError::Overflow)
+        ));
+    }
+
+    #[test]
+    fn accepts_short_uris() {
+        let req = TestRequest::get().uri("/advisories?location=x").to_http_request();
+        assert!(check_uri_length(&req, &SizeLimits::default()).is_none());
+    }
+
+    #[test]
+    fn rejects_long_query_strings() {
+        let limits = SizeLimits {
+            max_query_len: 8,
+            ..SizeLimits::default()
+        };
+        let req = TestRequest::get()
+            .uri("/advisories?location=something-much-longer-than-eight-bytes")
+            .to_http_request();
+        let response = check_uri_length(&req, &limits).expect("should reject");
+        assert_eq!(response.status(), StatusCode::URI_TOO_LONG);
+    }
+}